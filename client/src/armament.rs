@@ -3,11 +3,11 @@
 
 use crate::game::Mk48Game;
 use crate::interpolated_contact::InterpolatedContact;
-use client_util::context::CoreState;
 use client_util::renderer::particle::{Particle, ParticleLayer};
 use common::angle::Angle;
 use common::contact::{Contact, ContactTrait};
 use common::entity::{EntityData, EntityId, EntityKind, EntitySubKind};
+use common::protocol::AntiAircraftFire;
 use common::ticks::Ticks;
 use common::util::gen_radius;
 use glam::Vec2;
@@ -27,8 +27,8 @@ impl Mk48Game {
         let mut best_armament: Option<(usize, f32)> = None;
 
         if let Some(armament_selection) = armament_selection {
-            for i in 0..player_contact.data().armaments.len() {
-                let armament = &player_contact.data().armaments[i];
+            for i in 0..player_contact.armaments().len() {
+                let armament = &player_contact.armaments()[i];
 
                 let armament_entity_data: &EntityData = armament.entity_type.data();
 
@@ -44,6 +44,14 @@ impl Mk48Game {
                     continue;
                 }
 
+                if player_contact
+                    .subsystems()
+                    .map_or(false, |s| s.is_armament_disabled(i))
+                {
+                    // Damaged; cannot fire.
+                    continue;
+                }
+
                 if let Some(turret_index) = armament.turret {
                     if !player_contact.data().turrets[turret_index]
                         .within_azimuth(player_contact.turrets()[turret_index])
@@ -54,9 +62,11 @@ impl Mk48Game {
                 }
 
                 let transform = *player_contact.transform()
-                    + player_contact
-                        .data()
-                        .armament_transform(player_contact.turrets(), i);
+                    + player_contact.data().armament_transform(
+                        player_contact.loadout(),
+                        player_contact.turrets(),
+                        i,
+                    );
 
                 let armament_direction_target = Angle::from(mouse_position - transform.position);
 
@@ -96,45 +106,31 @@ impl Mk48Game {
         best_armament.map(|(idx, _)| idx)
     }
 
-    /// This approximates the server-based automatic anti aircraft gunfire, in the form
-    /// of tracer particles and audio (return value is appropriate volume).
+    /// This renders the server's automatic anti aircraft gunfire, in the form of tracer particles
+    /// and audio (return value is appropriate volume).
     pub fn simulate_anti_aircraft(
-        boat: &Contact,
+        anti_aircraft: &[AntiAircraftFire],
         contacts: &HashMap<EntityId, InterpolatedContact>,
-        core_state: &CoreState,
         player_position: Vec2,
         airborne_particles: &mut ParticleLayer,
     ) -> f32 {
         let mut volume = 0.0;
 
-        let data = boat.data();
         let mut rng = thread_rng();
-        // Anti-aircraft particles.
-        for InterpolatedContact {
-            view: aa_target, ..
-        } in contacts.values()
-        {
-            if aa_target.entity_type().map(|t| t.data().kind) != Some(EntityKind::Aircraft) {
-                // Not an aircraft.
-                continue;
-            }
-
-            let distance_squared = boat
-                .transform()
-                .position
-                .distance_squared(aa_target.transform().position);
-            if distance_squared > data.anti_aircraft_range().powi(2) {
-                // Out of range.
-                continue;
-            }
-
-            if rng.gen::<f32>() > data.anti_aircraft {
-                // Not powerful enough.
-                continue;
-            }
-
-            if core_state.are_friendly(boat.player_id(), aa_target.player_id()) {
-                // Don't shoot at friendly aircraft.
+        for fire in anti_aircraft {
+            let (boat, aa_target) = match (contacts.get(&fire.boat), contacts.get(&fire.aircraft)) {
+                (Some(boat), Some(aa_target)) => (&boat.view, &aa_target.view),
+                // Either is no longer visible.
+                _ => continue,
+            };
+
+            let data = match boat.entity_type() {
+                Some(entity_type) => entity_type.data(),
+                None => continue,
+            };
+
+            if rng.gen_bool(0.5) {
+                // Fire is sent every tick, but frames are more frequent than ticks.
                 continue;
             }
 
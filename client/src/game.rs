@@ -26,9 +26,11 @@ use client_util::rgb::{gray, rgb, rgba};
 use common::altitude::Altitude;
 use common::angle::Angle;
 use common::contact::{Contact, ContactTrait};
+use common::current::Currents;
 use common::entity::{EntityData, EntityId, EntityKind, EntitySubKind, EntityType};
 use common::guidance::Guidance;
-use common::protocol::{Command, Control, Fire, Hint, Pay, Spawn, Update, Upgrade};
+use common::harbor::Harbor;
+use common::protocol::{Buy, Command, Control, Event, Fire, Hint, Pay, Spawn, Update, Upgrade};
 use common::ticks::Ticks;
 use common::transform::Transform;
 use common::util::score_to_level;
@@ -39,6 +41,7 @@ use core_protocol::id::{GameId, TeamId};
 use core_protocol::name::PlayerAlias;
 use core_protocol::rpc::ClientRequest;
 use glam::{Mat2, UVec2, Vec2, Vec4};
+use rand::rngs::ThreadRng;
 use rand::{thread_rng, Rng};
 use std::collections::HashMap;
 
@@ -137,7 +140,7 @@ impl GameClient for Mk48Game {
             serde_json::from_str(include_str!("./sprites_audio.json")).unwrap();
         let sprite_sheet = serde_json::from_str(include_str!("./sprites_webgl.json")).unwrap();
         let sprite_texture =
-            renderer.load_texture("/sprites_webgl.png", UVec2::new(2048, 2048), None, false);
+            renderer.load_texture("/sprites_webgl.png", UVec2::new(4096, 4096), None, false);
 
         let background_context = Mk48BackgroundContext::new(
             renderer,
@@ -333,6 +336,14 @@ impl GameClient for Mk48Game {
         {
             Self::play_music("achievement", &layer.audio);
         }
+
+        let time_seconds = context.client.update_seconds;
+        context.game_mut().events.extend(
+            update
+                .events
+                .iter()
+                .map(|event| (time_seconds, event.clone())),
+        );
     }
 
     fn peek_mouse(&mut self, event: &MouseEvent, _context: &mut Context<Self>) {
@@ -449,7 +460,12 @@ impl GameClient for Mk48Game {
             area,
         );
 
-        let mut anti_aircraft_volume = 0.0;
+        let anti_aircraft_volume = Self::simulate_anti_aircraft(
+            &game_state.anti_aircraft,
+            &game_state.contacts,
+            renderer.camera_center(),
+            &mut layer.airborne_particles,
+        );
 
         // Update animations.
         let mut i = 0;
@@ -469,6 +485,65 @@ impl GameClient for Mk48Game {
             }
         }
 
+        // Forget old events.
+        let time_seconds = context.client.update_seconds;
+        game_state
+            .events
+            .retain(|(time, _)| time_seconds - time < Self::EVENT_DURATION);
+
+        // Show damage numbers above boats the player recently hit.
+        for (time, event) in game_state.events.iter() {
+            if let Event::Hit { target, damage, .. } = event {
+                let age = time_seconds - time;
+                if age > Self::DAMAGE_NUMBER_DURATION {
+                    continue;
+                }
+                if let Some((contact, data)) = game_state
+                    .contacts
+                    .get(target)
+                    .and_then(|c| Some((&c.view, c.view.entity_type()?.data())))
+                {
+                    let percent = damage.to_secs() * 100.0 / data.max_health().to_secs();
+                    let alpha = 1.0 - age / Self::DAMAGE_NUMBER_DURATION;
+                    layer.text.add(
+                        format!("-{:.0}%", percent.max(1.0)),
+                        contact.transform().position
+                            + Vec2::new(0.0, data.radius + (0.02 + age * 0.05) * zoom),
+                        0.03 * zoom,
+                        rgb(255, 200, 60).extend(alpha),
+                    );
+                }
+            }
+        }
+
+        // Outline harbors, and label them so players know they can't use weapons there.
+        for harbor in game_state.harbors.iter() {
+            let color = rgb(100, 180, 255).extend(0.5);
+            layer
+                .graphics
+                .add_circle(harbor.position, Harbor::RADIUS, 0.0025 * zoom, color);
+            layer.text.add(
+                String::from("Harbor"),
+                harbor.position + Vec2::new(0.0, Harbor::RADIUS),
+                0.03 * zoom,
+                color,
+            );
+        }
+
+        // Outline the storm front, so players know why their sensors are limited.
+        if let Some(storm) = game_state.storm.as_ref() {
+            let color = gray(160).extend(0.5);
+            layer
+                .graphics
+                .add_circle(storm.position, storm.radius, 0.0025 * zoom, color);
+            layer.text.add(
+                String::from("Storm"),
+                storm.position + Vec2::new(0.0, storm.radius),
+                0.03 * zoom,
+                color,
+            );
+        }
+
         // Update trails.
         game_state.trails.set_time(context.client.update_seconds);
 
@@ -499,22 +574,9 @@ impl GameClient for Mk48Game {
                 let data: &'static EntityData = entity_type.data();
                 let parent_type = entity_type;
 
-                if contact.is_boat()
-                    && !contact.altitude().is_submerged()
-                    && data.anti_aircraft > 0.0
-                {
-                    anti_aircraft_volume += Self::simulate_anti_aircraft(
-                        contact,
-                        &game_state.contacts,
-                        core_state,
-                        renderer.camera_center(),
-                        &mut layer.airborne_particles,
-                    );
-                }
-
                 if contact.is_boat() {
-                    for i in 0..data.armaments.len() {
-                        let armament = &data.armaments[i];
+                    for i in 0..contact.armaments().len() {
+                        let armament = &contact.armaments()[i];
                         if armament.hidden
                             || armament.vertical
                             || !(armament.external || (friendly && !context.ui.cinematic))
@@ -525,7 +587,8 @@ impl GameClient for Mk48Game {
                             entity_id,
                             parent_type,
                             armament.entity_type,
-                            *contact.transform() + data.armament_transform(contact.turrets(), i),
+                            *contact.transform()
+                                + data.armament_transform(contact.loadout(), contact.turrets(), i),
                             altitude + 0.02,
                             alpha
                                 * if contact
@@ -663,7 +726,7 @@ impl GameClient for Mk48Game {
                             if let Some(i) = context.mouse.world_position.and_then(|mouse_pos| {
                                 Self::find_best_armament(contact, false, mouse_pos, ui_armament)
                             }) {
-                                let armament = &data.armaments[i];
+                                let armament = &contact.armaments()[i];
                                 if armament.entity_type != EntityType::Depositor {
                                     if let Some(turret_index) = armament.turret {
                                         let turret = &data.turrets[turret_index];
@@ -774,7 +837,10 @@ impl GameClient for Mk48Game {
                         }
 
                         // Name
-                        let text = if let Some(player) =
+                        let text = if contact.player_id().unwrap().is_npc() {
+                            // Non-player characters go by the name of their ship.
+                            contact.data().label.clone()
+                        } else if let Some(player) =
                             core_state.player_or_bot(contact.player_id().unwrap())
                         {
                             if let Some(team) = player
@@ -850,6 +916,13 @@ impl GameClient for Mk48Game {
                         let start = contact.transform().position;
                         let end = start + direction_vector * speed * elapsed_seconds;
 
+                        // Wakes drift with ocean currents.
+                        let drift = if contact.altitude().is_airborne() {
+                            Vec2::ZERO
+                        } else {
+                            game_state.currents.sample(start)
+                        };
+
                         let factor = 1.0 / amount as f32;
                         for i in 0..amount {
                             let r = rng.gen::<f32>() - 0.5;
@@ -859,7 +932,8 @@ impl GameClient for Mk48Game {
                                 + tangent_vector * (data.width * r * 0.25);
 
                             let velocity = direction_vector * (speed * 0.75)
-                                + tangent_vector * (speed * r * spread);
+                                + tangent_vector * (speed * r * spread)
+                                + drift;
 
                             layer.add(Particle {
                                 position,
@@ -893,6 +967,40 @@ impl GameClient for Mk48Game {
                         }
                     }
                 }
+
+                // Fire and flooding particles, spread along the hull.
+                let afflictions = contact.afflictions();
+                if afflictions.any() {
+                    let along_hull = |rng: &mut ThreadRng, scale: f32| {
+                        contact.transform().position
+                            + direction_vector * (rng.gen::<f32>() - 0.5) * data.length * scale
+                            + tangent_vector * (rng.gen::<f32>() - 0.5) * data.width * scale
+                    };
+
+                    if afflictions.burning {
+                        for _ in 0..amount * 2 {
+                            layer.airborne_particles.add(Particle {
+                                position: along_hull(&mut rng, 0.6),
+                                velocity: gen_radius(&mut rng, 4.0),
+                                radius: 1.5,
+                                color: -1.0,
+                                smoothness: 1.0,
+                            });
+                        }
+                    }
+
+                    if afflictions.flooding {
+                        for _ in 0..amount {
+                            layer.sea_level_particles.add(Particle {
+                                position: along_hull(&mut rng, 1.0),
+                                velocity: gen_radius(&mut rng, 2.0),
+                                radius: 1.0,
+                                color: 1.0,
+                                smoothness: 1.0,
+                            });
+                        }
+                    }
+                }
             } else {
                 layer.sprites.add(
                     "contact",
@@ -907,6 +1015,28 @@ impl GameClient for Mk48Game {
 
         game_state.trails.update(&mut layer.airborne_graphics);
 
+        // Ocean currents carry flotsam, more of it where they are stronger.
+        let mut rng = thread_rng();
+        for _ in 0..((4.0 * particle_multiplier) as usize).max(1) {
+            let position = camera + gen_radius(&mut rng, zoom);
+            let current = game_state.currents.sample(position);
+            if rng.gen::<f32>() * Currents::MAX_SPEED > current.length()
+                || game_state
+                    .terrain
+                    .sample(position)
+                    .map_or(true, |altitude| altitude >= Altitude::ZERO)
+            {
+                continue;
+            }
+            layer.sea_level_particles.add(Particle {
+                position,
+                velocity: current,
+                color: 1.0,
+                radius: zoom * 0.005,
+                smoothness: 1.0,
+            });
+        }
+
         // Play anti-aircraft sfx.
         if anti_aircraft_volume > 0.0 && !layer.audio.is_playing("aa") {
             layer
@@ -1030,11 +1160,17 @@ impl GameClient for Mk48Game {
 
             let status = UiStatus::Playing {
                 entity_type: player_contact.entity_type().unwrap(),
+                loadout: player_contact.loadout(),
                 position: player_contact.transform().position.into(),
                 direction: player_contact.transform().direction,
                 velocity: player_contact.transform().velocity,
                 altitude: player_contact.altitude(),
                 armament_consumption: Some(player_contact.reloads().into()), // TODO fix to clone arc
+                subsystems: player_contact.subsystems().cloned(),
+                harbor: game_state
+                    .harbors
+                    .iter()
+                    .any(|harbor| harbor.contains(player_contact.transform().position)),
             };
 
             if self.control_rate_limiter.update_ready(elapsed_seconds) {
@@ -1121,20 +1257,32 @@ impl GameClient for Mk48Game {
         layer: &mut Self::RendererLayer,
     ) {
         match event {
-            UiEvent::Spawn { alias, entity_type } => {
+            UiEvent::Spawn {
+                alias,
+                entity_type,
+                loadout,
+            } => {
                 context.send_to_core(ClientRequest::IdentifySession {
                     alias: PlayerAlias::new(alias),
                 });
                 context.send_to_game(Command::Spawn(Spawn {
                     entity_type: *entity_type,
+                    loadout: *loadout,
                 }))
             }
-            UiEvent::Upgrade(entity_type) => {
+            UiEvent::Upgrade {
+                entity_type,
+                loadout,
+            } => {
                 layer.audio.play("upgrade");
                 context.send_to_game(Command::Upgrade(Upgrade {
                     entity_type: *entity_type,
+                    loadout: *loadout,
                 }))
             }
+            UiEvent::Buy(consumable) => context.send_to_game(Command::Buy(Buy {
+                consumable: *consumable,
+            })),
             UiEvent::Active(active) => {
                 if let Some(contact) = context.game().player_contact() {
                     if *active && contact.data().sensors.sonar.range >= 0.0 {
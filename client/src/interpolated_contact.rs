@@ -88,6 +88,7 @@ impl InterpolatedContact {
         if let Some(entity_type) = self.model.entity_type() {
             let data: &EntityData = entity_type.data();
             if self.view.entity_type() == self.model.entity_type()
+                && self.view.loadout() == self.model.loadout()
                 && self.view.reloads_known()
                 && self.model.reloads_known()
                 && self.view.turrets_known()
@@ -101,7 +102,7 @@ impl InterpolatedContact {
                         continue;
                     }
 
-                    let armament = &data.armaments[i];
+                    let armament = &self.view.armaments()[i];
                     let armament_entity_data = armament.entity_type.data();
 
                     if !matches!(
@@ -118,8 +119,8 @@ impl InterpolatedContact {
                     let boat_velocity = self.view.transform().direction.to_vec()
                         * self.view.transform().velocity.to_mps();
 
-                    let armament_transform =
-                        *self.view.transform() + data.armament_transform(self.view.turrets(), i);
+                    let armament_transform = *self.view.transform()
+                        + data.armament_transform(self.view.loadout(), self.view.turrets(), i);
 
                     let direction_vector: Vec2 = if armament.vertical {
                         // Straight up.
@@ -199,6 +200,8 @@ impl Mk48Game {
             let name = match entity_type.data().kind {
                 EntityKind::Boat | EntityKind::Aircraft => "splash",
                 EntityKind::Weapon => match entity_type.data().sub_kind {
+                    // Ballistic shells throw up a column of water where they land.
+                    EntitySubKind::Shell if entity_type.data().is_ballistic() => "splash",
                     EntitySubKind::Missile
                     | EntitySubKind::Sam
                     | EntitySubKind::Rocket
@@ -222,7 +225,11 @@ impl Mk48Game {
 
             // The more damage/health the entity has the larger its explosion is.
             debug_assert!(data.damage >= 0.0);
-            let scale = (data.damage.sqrt() * 10.0).clamp(5.0, 40.0);
+            let scale = if data.is_ballistic() {
+                data.splash * 2.0
+            } else {
+                (data.damage.sqrt() * 10.0).clamp(5.0, 40.0)
+            };
 
             animations.push(Animation::new(
                 name,
@@ -311,11 +318,15 @@ impl Mk48Game {
                         audio_layer.play_with_volume("alarm_slow", 0.1 * volume.max(0.5));
                     }
                 }
-                EntityKind::Decoy => {
-                    if data.sub_kind == EntitySubKind::Sonar {
+                EntityKind::Decoy => match data.sub_kind {
+                    EntitySubKind::Radar => {
+                        audio_layer.play_with_volume("rocket", volume);
+                    }
+                    EntitySubKind::Sonar => {
                         audio_layer.play_with_volume("sonar3", volume);
                     }
-                }
+                    _ => {}
+                },
                 _ => {}
             }
         }
@@ -5,20 +5,36 @@ use crate::animation::Animation;
 use crate::interpolated_contact::InterpolatedContact;
 use crate::trail::TrailSystem;
 use client_util::apply::Apply;
+use common::consumable::Consumable;
 use common::contact::Contact;
+use common::current::Currents;
 use common::death_reason::DeathReason;
 use common::entity::EntityId;
-use common::protocol::Update;
+use common::harbor::Harbor;
+use common::protocol::{AntiAircraftFire, Event, Update};
+use common::storm::Storm;
 use common::terrain::Terrain;
 use std::collections::HashMap;
 
 /// State associated with game server connection. Reset when connection is reset.
 pub struct Mk48State {
     pub animations: Vec<Animation>,
+    /// Anti-aircraft gunfire from the latest update.
+    pub anti_aircraft: Vec<AntiAircraftFire>,
+    /// Consumables bought this life.
+    pub consumables: Vec<Consumable>,
     pub contacts: HashMap<EntityId, InterpolatedContact>,
+    /// Ocean currents near the camera, from the latest update that included them.
+    pub currents: Currents,
     pub death_reason: Option<DeathReason>,
     pub entity_id: Option<EntityId>,
+    /// Recent events, and when they were received (in seconds).
+    pub events: Vec<(f32, Event)>,
+    /// Harbors near the camera, from the latest update.
+    pub harbors: Vec<Harbor>,
     pub score: u32,
+    /// Storm front, from the latest update.
+    pub storm: Option<Storm>,
     pub terrain: Terrain,
     pub trails: TrailSystem,
     pub world_radius: f32,
@@ -28,10 +44,16 @@ impl Default for Mk48State {
     fn default() -> Self {
         Self {
             animations: Vec::new(),
+            anti_aircraft: Vec::new(),
+            consumables: Vec::new(),
             contacts: HashMap::new(),
+            currents: Currents::default(),
             death_reason: None,
             entity_id: None,
+            events: Vec::new(),
+            harbors: Vec::new(),
             score: 0,
+            storm: None,
             terrain: Terrain::default(),
             trails: TrailSystem::default(),
             // Keep border off splash screen by assuming radius.
@@ -50,7 +72,24 @@ impl Mk48State {
 
 impl Apply<Update> for Mk48State {
     fn apply(&mut self, update: Update) {
+        self.anti_aircraft = update.anti_aircraft;
+        if let Some(currents) = update.currents {
+            self.currents = currents;
+        }
+        if update.death_reason.is_some() {
+            self.consumables.clear();
+        }
+        self.consumables
+            .extend(update.events.iter().filter_map(|event| {
+                if let Event::Bought(consumable) = event {
+                    Some(*consumable)
+                } else {
+                    None
+                }
+            }));
         self.death_reason = update.death_reason;
+        self.harbors = update.harbors;
+        self.storm = update.storm;
         self.terrain.apply_update(&update.terrain);
         self.world_radius = update.world_radius;
         self.score = update.score;
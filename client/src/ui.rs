@@ -6,8 +6,11 @@ use client_util::apply::Apply;
 use client_util::context::{Context, CoreState};
 use common::altitude::Altitude;
 use common::angle::Angle;
+use common::consumable::Consumable;
 use common::death_reason::DeathReason;
 use common::entity::{EntityKind, EntitySubKind, EntityType};
+use common::protocol::Event;
+use common::subsystems::Subsystems;
 use common::ticks::Ticks;
 use common::velocity::Velocity;
 use common::world::outside_area;
@@ -44,8 +47,16 @@ pub enum UiEvent {
         alias: String,
         #[serde(rename = "entityType")]
         entity_type: EntityType,
+        #[serde(default)]
+        loadout: u8,
     },
-    Upgrade(EntityType),
+    Upgrade {
+        #[serde(rename = "entityType")]
+        entity_type: EntityType,
+        #[serde(default)]
+        loadout: u8,
+    },
+    Buy(Consumable),
     /// Sensors active.
     Active(bool),
     /// Normalized altitude target.
@@ -88,6 +99,10 @@ pub struct UiProps {
     pub team_join_requests: Vec<TeamPlayerModel>,
     pub teams: Vec<TeamModel>,
     pub restrictions: Vec<EntityType>, // Entity types that can't be used.
+    /// Recent kills and assists.
+    pub feed: Vec<FeedItemModel>,
+    /// Consumables bought this life.
+    pub consumables: Vec<Consumable>,
 }
 
 /// Mutually exclusive statuses.
@@ -100,12 +115,18 @@ pub enum UiStatus {
     Playing {
         #[serde(rename = "type")]
         entity_type: EntityType,
+        /// Which of the boat's loadouts it is armed with.
+        loadout: u8,
         velocity: Velocity,
         direction: Angle,
         position: Vec2Model,
         altitude: Altitude,
         #[serde(skip_serializing_if = "Option::is_none")]
         armament_consumption: Option<Arc<[Ticks]>>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        subsystems: Option<Subsystems>,
+        /// Whether in a harbor, where weapons cannot be used.
+        harbor: bool,
     },
     #[serde(rename_all = "camelCase")]
     Respawning {
@@ -147,6 +168,20 @@ pub struct LeaderboardItemModel {
     pub name: PlayerAlias,
     pub team: Option<TeamName>,
     pub score: u32,
+    /// Always zero for period leaderboards.
+    pub bounty: u32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedItemModel {
+    /// Either "sunk" or "assist".
+    #[serde(rename = "type")]
+    pub feed_type: &'static str,
+    /// None if the boat belonged to a non-player character.
+    pub player: Option<PlayerAlias>,
+    pub entity: EntityType,
+    pub score: u32,
 }
 
 #[derive(Serialize)]
@@ -230,6 +265,11 @@ impl From<Vec2> for Vec2Model {
 }
 
 impl Mk48Game {
+    /// How long (in seconds) to keep events, such as to show them in the feed.
+    pub(crate) const EVENT_DURATION: f32 = 5.0;
+    /// How long (in seconds) to show damage numbers for.
+    pub(crate) const DAMAGE_NUMBER_DURATION: f32 = 1.5;
+
     pub(crate) fn update_ui_props(
         &self,
         context: &mut Context<Self>,
@@ -270,6 +310,7 @@ impl Mk48Game {
                             name: player.alias,
                             team: team_name,
                             score: item.score,
+                            bounty: item.bounty,
                         })
                     } else {
                         None
@@ -291,6 +332,7 @@ impl Mk48Game {
                                 name: item.alias,
                                 team: None,
                                 score: item.score,
+                                bounty: 0,
                             })
                             .collect(),
                     )
@@ -356,6 +398,37 @@ impl Mk48Game {
                     }
                 })
                 .collect(),
+            feed: context
+                .game()
+                .events
+                .iter()
+                .filter_map(|(_, event)| {
+                    let (feed_type, victim, entity_type, score) = match event {
+                        Event::Sunk {
+                            victim,
+                            entity_type,
+                            score,
+                            ..
+                        } => ("sunk", victim, entity_type, score),
+                        Event::Assist {
+                            victim,
+                            entity_type,
+                            score,
+                            ..
+                        } => ("assist", victim, entity_type, score),
+                        _ => return None,
+                    };
+                    Some(FeedItemModel {
+                        feed_type,
+                        player: (!victim.is_npc())
+                            .then(|| core_state.player_or_bot(*victim).map(|p| p.alias))
+                            .flatten(),
+                        entity: *entity_type,
+                        score: *score,
+                    })
+                })
+                .collect(),
+            consumables: context.game().consumables.clone(),
             status,
         };
 
@@ -0,0 +1,49 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::entity::EntitySubKind;
+use crate::ticks::Ticks;
+use serde::{Deserialize, Serialize};
+
+/// Affliction is a status effect that damages a boat over time, until it subsides.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Affliction {
+    Fire,
+    Flooding,
+}
+
+impl Affliction {
+    pub const ALL: [Self; 2] = [Self::Fire, Self::Flooding];
+
+    /// caused_by returns the affliction a weapon may cause, if any, and the chance of it doing so
+    /// per hit.
+    pub fn caused_by(weapon_sub_kind: EntitySubKind) -> Option<(Self, f64)> {
+        match weapon_sub_kind {
+            EntitySubKind::Shell => Some((Self::Fire, 0.25)),
+            EntitySubKind::Torpedo => Some((Self::Flooding, 0.5)),
+            _ => None,
+        }
+    }
+
+    /// kill_time returns how long a single stack of the affliction takes to sink an undamaged boat.
+    pub fn kill_time(self) -> Ticks {
+        match self {
+            Self::Fire => Ticks::from_secs(90.0),
+            Self::Flooding => Ticks::from_secs(60.0),
+        }
+    }
+}
+
+/// Afflictions are the visible signs of fire and flooding on a boat.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Afflictions {
+    pub burning: bool,
+    pub flooding: bool,
+}
+
+impl Afflictions {
+    /// any returns whether the boat is afflicted at all.
+    pub fn any(self) -> bool {
+        self.burning || self.flooding
+    }
+}
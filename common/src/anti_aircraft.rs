@@ -0,0 +1,78 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::angle::Angle;
+use crate::transform::Transform;
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+/// AntiAircraft is a boat's automatic anti-aircraft guns, which engage hostile aircraft on their own.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AntiAircraft {
+    /// Chance per second of shooting down a slow aircraft at point blank range, if it is the only
+    /// one being engaged.
+    pub power: f32,
+    /// Maximum distance at which aircraft are engaged.
+    pub range: f32,
+    /// Half-angle of the sector, centered on each beam (side), that the guns can cover. Anything
+    /// less than 90 degrees leaves blind spots towards the bow and stern.
+    pub arc: Angle,
+    /// How many aircraft can be engaged before the guns' attention is divided between them.
+    pub channels: u8,
+}
+
+impl AntiAircraft {
+    /// Aircraft faster than this (in meters per second) are harder to hit.
+    const REFERENCE_SPEED: f32 = 50.0;
+
+    /// covers returns whether the guns of a boat, with a given transform, can engage an aircraft
+    /// at a given position (ignoring range).
+    pub fn covers(&self, boat: &Transform, position: Vec2) -> bool {
+        let relative = (Angle::from(position - boat.position) - boat.direction).abs();
+        (relative - Angle::PI_2).abs() <= self.arc
+    }
+
+    /// kill_chance returns the chance per second of shooting down an aircraft at a given distance
+    /// and speed (in meters per second), given the total number of aircraft being engaged.
+    pub fn kill_chance(&self, distance: f32, speed: f32, engaged: usize) -> f32 {
+        if distance > self.range {
+            return 0.0;
+        }
+        let proximity = 1.0 - (distance / self.range).powi(2);
+        let tracking = (Self::REFERENCE_SPEED / speed.max(1.0)).sqrt().min(1.0);
+        let saturation = (self.channels as f32 / engaged.max(1) as f32).min(1.0);
+        self.power * proximity * tracking * saturation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entity::EntityType;
+    use crate::transform::Transform;
+    use glam::Vec2;
+
+    #[test]
+    fn anti_aircraft() {
+        unsafe {
+            EntityType::init();
+        }
+
+        let anti_aircraft = EntityType::Fletcher.data().anti_aircraft.as_ref().unwrap();
+        let boat = Transform::new();
+        let range = anti_aircraft.range;
+
+        // Broadside is covered, whereas dead ahead and astern are blind spots.
+        assert!(anti_aircraft.covers(&boat, Vec2::new(0.0, range * 0.5)));
+        assert!(anti_aircraft.covers(&boat, Vec2::new(0.0, range * -0.5)));
+        assert!(!anti_aircraft.covers(&boat, Vec2::new(range * 0.5, 0.0)));
+        assert!(!anti_aircraft.covers(&boat, Vec2::new(range * -0.5, 0.0)));
+
+        // Closer, slower, and fewer aircraft are easier to shoot down.
+        let chance = anti_aircraft.kill_chance(range * 0.5, 30.0, 1);
+        assert!(chance > anti_aircraft.kill_chance(range * 0.9, 30.0, 1));
+        assert!(chance > anti_aircraft.kill_chance(range * 0.5, 150.0, 1));
+        assert!(chance > anti_aircraft.kill_chance(range * 0.5, 30.0, 8));
+        assert_eq!(anti_aircraft.kill_chance(range * 1.5, 30.0, 1), 0.0);
+    }
+}
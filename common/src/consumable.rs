@@ -0,0 +1,93 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::entity::EntityType;
+use crate::ticks::Ticks;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Consumable is something that players can buy with coins, a limited number of times per life,
+/// to help their boat.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Consumable {
+    EmergencyRepair,
+    Reload,
+    SensorBoost,
+    Smoke,
+}
+
+/// ConsumableData is the effect and price of a consumable, defined in data.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsumableData {
+    pub label: String,
+    /// Price, in coins.
+    pub cost: u32,
+    /// How many times it can be bought per life.
+    pub limit: u8,
+    /// Fraction of maximum health restored.
+    #[serde(default)]
+    pub repair: f32,
+    /// Whether all fire and flooding are put out.
+    #[serde(default)]
+    pub extinguish: bool,
+    /// Whether all armaments are instantly reloaded (except limited armaments that are deployed).
+    #[serde(default)]
+    pub reload: bool,
+    /// How long any lasting effects last.
+    #[serde(default)]
+    pub duration: Ticks,
+    /// Multiplier of the boat's sensor ranges, while in effect.
+    #[serde(default = "one")]
+    pub sensor_factor: f32,
+    /// Multiplier of the range at which the boat can be seen, while in effect.
+    #[serde(default = "one")]
+    pub visibility_factor: f32,
+}
+
+fn one() -> f32 {
+    1.0
+}
+
+lazy_static! {
+    static ref CONSUMABLE_DATA: HashMap<Consumable, ConsumableData> =
+        serde_json::from_str(include_str!("../../js/src/data/consumables.json"))
+            .expect("could not parse consumable json");
+}
+
+impl Consumable {
+    pub const ALL: [Self; 4] = [
+        Self::EmergencyRepair,
+        Self::Reload,
+        Self::SensorBoost,
+        Self::Smoke,
+    ];
+
+    /// data returns the data of the consumable.
+    pub fn data(self) -> &'static ConsumableData {
+        &CONSUMABLE_DATA[&self]
+    }
+}
+
+impl ConsumableData {
+    /// price returns the score that buying the consumable costs.
+    pub fn price(&self) -> u32 {
+        self.cost * EntityType::Coin.data().value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::consumable::Consumable;
+
+    #[test]
+    fn data() {
+        for consumable in Consumable::ALL {
+            let data = consumable.data();
+            assert!(data.cost > 0, "{:?}", consumable);
+            assert!(data.limit > 0, "{:?}", consumable);
+        }
+    }
+}
@@ -1,10 +1,12 @@
 // SPDX-FileCopyrightText: 2021 Softbear, Inc.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use crate::affliction::Afflictions;
 use crate::altitude::Altitude;
 use crate::angle::Angle;
 use crate::entity::*;
 use crate::guidance::Guidance;
+use crate::subsystems::Subsystems;
 use crate::ticks::Ticks;
 use crate::transform::Transform;
 use crate::util::make_mut_slice;
@@ -19,6 +21,9 @@ use std::iter::repeat_with;
 use std::sync::Arc;
 
 pub trait ContactTrait {
+    /// Visible fire and flooding, if a boat.
+    fn afflictions(&self) -> Afflictions;
+
     fn altitude(&self) -> Altitude;
 
     fn damage(&self) -> Ticks;
@@ -29,6 +34,9 @@ pub trait ContactTrait {
 
     fn id(&self) -> EntityId;
 
+    /// Which of a boat's loadouts it is armed with (0 if not a boat).
+    fn loadout(&self) -> u8;
+
     fn player_id(&self) -> Option<PlayerId>;
 
     fn reloads(&self) -> &[Ticks];
@@ -36,6 +44,9 @@ pub trait ContactTrait {
     /// Whether reloads() will return real data or all zeroes.
     fn reloads_known(&self) -> bool;
 
+    /// Damage to individual parts of a boat, only known to its owner.
+    fn subsystems(&self) -> Option<&Subsystems>;
+
     fn transform(&self) -> &Transform;
 
     fn turrets(&self) -> &[Angle];
@@ -53,31 +64,43 @@ pub trait ContactTrait {
     fn data(&self) -> &'static EntityData {
         self.entity_type().unwrap().data()
     }
+
+    /// Armaments of the contact's loadout.
+    #[inline]
+    fn armaments(&self) -> &'static [Armament] {
+        self.data().loadout_armaments(self.loadout())
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Contact {
     transform: Transform,
+    afflictions: Afflictions,
     altitude: Altitude,
     guidance: Guidance,
     damage: Ticks,
     entity_type: Option<EntityType>,
     id: EntityId,
+    loadout: u8,
     player_id: Option<PlayerId>,
     reloads: Option<Arc<[Ticks]>>,
+    subsystems: Option<Subsystems>,
     turrets: Option<Arc<[Angle]>>,
 }
 
 impl Default for Contact {
     fn default() -> Self {
         Self {
+            afflictions: Afflictions::default(),
             altitude: Altitude::default(),
             damage: Ticks::default(),
             entity_type: None,
             guidance: Guidance::default(),
             id: EntityId::new(u32::MAX).unwrap(),
+            loadout: 0,
             player_id: None,
             reloads: None,
+            subsystems: None,
             transform: Transform::default(),
             turrets: None,
         }
@@ -87,24 +110,30 @@ impl Default for Contact {
 impl Contact {
     /// Initializes all (private) fields.
     pub fn new(
+        afflictions: Afflictions,
         altitude: Altitude,
         damage: Ticks,
         entity_type: Option<EntityType>,
         guidance: Guidance,
         id: EntityId,
+        loadout: u8,
         player_id: Option<PlayerId>,
         reloads: Option<Arc<[Ticks]>>,
+        subsystems: Option<Subsystems>,
         transform: Transform,
         turrets: Option<Arc<[Angle]>>,
     ) -> Self {
         Self {
+            afflictions,
             altitude,
             damage,
             entity_type,
             guidance,
             id,
+            loadout,
             player_id,
             reloads,
+            subsystems,
             transform,
             turrets,
         }
@@ -155,11 +184,14 @@ impl Contact {
         // Upgraded.
         let changed_type = self.entity_type != model.entity_type;
         self.entity_type = model.entity_type;
+        self.loadout = model.loadout;
 
+        self.afflictions = model.afflictions;
         self.altitude = self.altitude.lerp(model.altitude, lerp);
         self.damage = model.damage;
         self.player_id = model.player_id;
         self.reloads = model.reloads.clone();
+        self.subsystems = model.subsystems.clone();
         if interpolate_guidance {
             self.guidance = model.guidance;
         }
@@ -217,6 +249,11 @@ pub static ANGLE_ARRAY_ZERO: [Angle; 0] = [Angle::ZERO; 0];
 pub static TICKS_ARRAY_ZERO: [Ticks; 0] = [Ticks::ZERO; 0];
 
 impl ContactTrait for Contact {
+    #[inline]
+    fn afflictions(&self) -> Afflictions {
+        self.afflictions
+    }
+
     fn altitude(&self) -> Altitude {
         self.altitude
     }
@@ -241,6 +278,11 @@ impl ContactTrait for Contact {
         self.id
     }
 
+    #[inline]
+    fn loadout(&self) -> u8 {
+        self.loadout
+    }
+
     #[inline]
     fn player_id(&self) -> Option<PlayerId> {
         self.player_id
@@ -258,6 +300,11 @@ impl ContactTrait for Contact {
         self.reloads.is_some()
     }
 
+    #[inline]
+    fn subsystems(&self) -> Option<&Subsystems> {
+        self.subsystems.as_ref()
+    }
+
     #[inline]
     fn transform(&self) -> &Transform {
         &self.transform
@@ -286,10 +333,13 @@ struct ContactHeader {
     has_type: bool,
     has_player_id: bool,
     has_reloads: bool,
+    has_subsystems: bool,
+    has_afflictions: bool,
+    has_loadout: bool,
 }
 
 impl ContactHeader {
-    fn as_bits(&self) -> u8 {
+    fn as_bits(&self) -> u16 {
         let bools = [
             self.has_vel,
             self.has_alt,
@@ -299,22 +349,25 @@ impl ContactHeader {
             self.has_type,
             self.has_player_id,
             self.has_reloads,
+            self.has_subsystems,
+            self.has_afflictions,
+            self.has_loadout,
         ];
 
-        let mut bits: u8 = 0;
+        let mut bits: u16 = 0;
         for (i, &bit) in bools.iter().enumerate() {
-            bits |= (bit as u8) << i;
+            bits |= (bit as u16) << i;
         }
         bits
     }
 
-    fn from_bits(bits: u8) -> Self {
-        let mut bools = [false; 8];
+    fn from_bits(bits: u16) -> Self {
+        let mut bools = [false; 11];
         for (i, bit) in bools.iter_mut().enumerate() {
             *bit = bits & (1 << i) != 0
         }
 
-        let [has_vel, has_alt, has_dir_target, has_vel_target, has_damage, has_type, has_player_id, has_reloads] =
+        let [has_vel, has_alt, has_dir_target, has_vel_target, has_damage, has_type, has_player_id, has_reloads, has_subsystems, has_afflictions, has_loadout] =
             bools;
 
         let header = Self {
@@ -326,13 +379,17 @@ impl ContactHeader {
             has_type,
             has_player_id,
             has_reloads,
+            has_subsystems,
+            has_afflictions,
+            has_loadout,
         };
         debug_assert_eq!(bits, header.as_bits());
         header
     }
 
     fn tuple_len(&self) -> usize {
-        12 - self.as_bits().count_zeros() as usize
+        // 3 required elements, optional elements, and 1 option or unit element.
+        4 + self.as_bits().count_ones() as usize
     }
 }
 
@@ -369,6 +426,9 @@ impl<'a> ContactSerializer<'a> {
                 has_damage: c.damage != Ticks::ZERO,
                 has_player_id: c.player_id.is_some(),
                 has_reloads: c.reloads.is_some(),
+                has_subsystems: c.subsystems.is_some(),
+                has_afflictions: c.afflictions.any(),
+                has_loadout: c.loadout != 0,
             },
         };
 
@@ -392,7 +452,7 @@ impl<'a> Serialize for ContactSerializer<'a> {
         tup.serialize_element(&self.c.transform.position)?;
         tup.serialize_element(&self.c.transform.direction)?;
 
-        // 8 optional elements.
+        // 11 optional elements.
         if self.h.has_vel {
             tup.serialize_element(&self.c.transform.velocity)?;
         }
@@ -411,6 +471,9 @@ impl<'a> Serialize for ContactSerializer<'a> {
         if self.h.has_type {
             tup.serialize_element(&self.c.entity_type.unwrap())?;
         }
+        if self.h.has_loadout {
+            tup.serialize_element(&self.c.loadout)?;
+        }
         if self.h.has_player_id {
             tup.serialize_element(&self.c.player_id)?;
         }
@@ -422,6 +485,12 @@ impl<'a> Serialize for ContactSerializer<'a> {
                 tup.serialize_element(&KnownSizeSerializer::new(reloads))?;
             }
         }
+        if self.h.has_subsystems {
+            tup.serialize_element(self.c.subsystems.as_ref().unwrap())?;
+        }
+        if self.h.has_afflictions {
+            tup.serialize_element(&self.c.afflictions)?;
+        }
 
         // 1 option or unit element.
         if self.c.is_boat() {
@@ -528,7 +597,7 @@ impl<'de, 'a> Visitor<'de> for ContactDeserializer<'a> {
         self.c.transform.position = seq.next_element()?.unwrap();
         self.c.transform.direction = seq.next_element()?.unwrap();
 
-        // 8 optional elements.
+        // 11 optional elements.
         if self.h.has_vel {
             self.c.transform.velocity = seq.next_element()?.unwrap();
         }
@@ -551,12 +620,15 @@ impl<'de, 'a> Visitor<'de> for ContactDeserializer<'a> {
         if self.h.has_type {
             self.c.entity_type = Some(seq.next_element()?.unwrap());
         }
+        if self.h.has_loadout {
+            self.c.loadout = seq.next_element()?.unwrap();
+        }
         if self.h.has_player_id {
             self.c.player_id = seq.next_element()?.unwrap();
         }
         if self.h.has_reloads {
-            // Must be after type is assigend.
-            let size = self.c.entity_type.unwrap().data().armaments.len();
+            // Must be after type and loadout are assigend.
+            let size = self.c.armaments().len();
             if size == 0 {
                 let _: () = seq.next_element()?.unwrap();
                 self.c.reloads = Some(Arc::new([]))
@@ -567,6 +639,12 @@ impl<'de, 'a> Visitor<'de> for ContactDeserializer<'a> {
                 );
             }
         }
+        if self.h.has_subsystems {
+            self.c.subsystems = Some(seq.next_element()?.unwrap());
+        }
+        if self.h.has_afflictions {
+            self.c.afflictions = seq.next_element()?.unwrap();
+        }
 
         // 1 option or unit element.
         if self.c.is_boat() {
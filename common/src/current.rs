@@ -0,0 +1,89 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+/// Currents are ocean current velocities (in meters per second), sampled on a coarse grid.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Currents {
+    /// Position of the first sample (with the lowest x and y).
+    pub origin: Vec2,
+    /// Number of samples per row.
+    pub width: u8,
+    /// Samples, in rows of increasing y.
+    pub velocities: Vec<Vec2>,
+}
+
+impl Currents {
+    /// Distance (in meters) between samples, which is enough because currents are large-scale.
+    pub const SPACING: f32 = 250.0;
+    /// Maximum speed (in meters per second) of currents.
+    pub const MAX_SPEED: f32 = 2.0;
+
+    /// new samples a current field (a function of position) on a grid covering a rectangle.
+    pub fn new(center: Vec2, dimensions: Vec2, field: impl Fn(Vec2) -> Vec2) -> Self {
+        let half = dimensions * 0.5;
+        let origin = ((center - half) * (1.0 / Self::SPACING)).floor() * Self::SPACING;
+        let end = ((center + half) * (1.0 / Self::SPACING)).ceil() * Self::SPACING;
+        let samples = ((end - origin) * (1.0 / Self::SPACING)).round().as_uvec2() + 1;
+        let width = samples.x.min(u8::MAX as u32) as u8;
+
+        let velocities = (0..samples.y)
+            .flat_map(|y| (0..width as u32).map(move |x| (x, y)))
+            .map(|(x, y)| field(origin + Vec2::new(x as f32, y as f32) * Self::SPACING))
+            .collect();
+
+        Self {
+            origin,
+            width,
+            velocities,
+        }
+    }
+
+    /// sample returns the current at a position, interpolating between the nearest samples (or
+    /// zero if there are no samples).
+    pub fn sample(&self, position: Vec2) -> Vec2 {
+        if self.width == 0 || self.velocities.is_empty() {
+            return Vec2::ZERO;
+        }
+
+        let width = self.width as usize;
+        let height = self.velocities.len() / width;
+        let max = Vec2::new((width - 1) as f32, (height - 1) as f32);
+        let grid = ((position - self.origin) * (1.0 / Self::SPACING)).clamp(Vec2::ZERO, max);
+
+        let floor = grid.floor();
+        let fract = grid - floor;
+        let (x, y) = (floor.x as usize, floor.y as usize);
+        let (x1, y1) = ((x + 1).min(width - 1), (y + 1).min(height - 1));
+        let at = |x: usize, y: usize| self.velocities[x + y * width];
+
+        at(x, y)
+            .lerp(at(x1, y), fract.x)
+            .lerp(at(x, y1).lerp(at(x1, y1), fract.x), fract.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::current::Currents;
+    use glam::Vec2;
+
+    #[test]
+    fn sample() {
+        let field = |position: Vec2| Vec2::new(position.x * 0.001, -position.y * 0.001);
+        let currents = Currents::new(Vec2::new(100.0, -300.0), Vec2::new(1000.0, 600.0), field);
+
+        for position in [
+            Vec2::new(100.0, -300.0),
+            Vec2::new(-300.0, -50.0),
+            Vec2::new(520.0, -560.0),
+        ] {
+            // Field is linear, so interpolation is exact.
+            assert!(currents.sample(position).distance(field(position)) < 0.001);
+        }
+
+        assert_eq!(Currents::default().sample(Vec2::ZERO), Vec2::ZERO);
+    }
+}
@@ -3,20 +3,24 @@
 
 use crate::altitude::Altitude;
 use crate::angle::Angle;
+use crate::anti_aircraft::AntiAircraft;
+use crate::handling::Handling;
+use crate::jammer::Jammer;
+use crate::seeker::Seeker;
 use crate::ticks;
 use crate::ticks::Ticks;
 use crate::transform::Transform;
 use crate::util::{level_to_score, natural_death_coins};
 use crate::velocity::Velocity;
-use arrayvec::ArrayVec;
 use core_protocol::serde_util::{StrVisitor, U8Visitor};
 use enum_iterator::IntoEnumIterator;
 use glam::Vec2;
 use macros::entity_type;
-use rand::seq::IteratorRandom;
+use rand::seq::{IteratorRandom, SliceRandom};
 use rand::{thread_rng, Rng};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::HashMap;
+use std::iter;
 use std::num::NonZeroU32;
 use std::ops::{Mul, Range, RangeInclusive};
 
@@ -80,6 +84,7 @@ pub enum EntitySubKind {
     Mtb,
     Pirate,
     Plane,
+    Radar,
     Ram,
     Rocket,
     RocketTorpedo,
@@ -92,6 +97,7 @@ pub enum EntitySubKind {
     Tanker,
     Torpedo,
     Tree,
+    Wreck,
 }
 
 #[allow(dead_code)]
@@ -113,8 +119,15 @@ pub struct EntityData {
     pub reload: Ticks,
     #[serde(default)]
     pub speed: Velocity, // Guaranteed to get the attention of any physics professor.
+    /// How a ship responds to guidance, if not instantly.
+    #[serde(default)]
+    pub handling: Option<Handling>,
     pub length: f32,
     pub width: f32,
+    /// Convex collision shape, counter-clockwise, with the bow at positive x. Defaults to a
+    /// rectangle of length and width.
+    #[serde(default)]
+    pub hull: Vec<Vec2>,
     #[serde(default)]
     pub draft: Altitude, // Type is a bit cheeky but kind of makes sense.
     #[serde(default)]
@@ -125,20 +138,48 @@ pub struct EntityData {
     pub inv_size: f32,
     #[serde(default)]
     pub damage: f32,
+    /// Radius of splash damage, if a ballistic shell (one that lands where it is aimed).
+    #[serde(default)]
+    pub splash: f32,
+    /// Automatic anti-aircraft guns, if any.
     #[serde(default)]
-    pub anti_aircraft: f32,
+    pub anti_aircraft: Option<AntiAircraft>,
     #[serde(default)]
     pub torpedo_resistance: f32,
     #[serde(default)]
     pub stealth: f32,
     #[serde(default)]
     pub sensors: Sensors,
+    /// How a homing weapon acquires targets.
+    #[serde(default)]
+    pub seeker: Option<Seeker>,
+    /// Noise emitted regardless of speed, which attracts seekers (e.g. a decoy's).
+    #[serde(default)]
+    pub noise: f32,
     #[serde(default)]
     pub armaments: Vec<Armament>,
+    /// Alternatives to armaments, numbered from 1 (0 being armaments itself).
+    #[serde(default)]
+    pub loadouts: Vec<Loadout>,
     #[serde(default)]
     pub turrets: Vec<Turret>,
     #[serde(default)]
     pub exhausts: Vec<Exhaust>,
+    /// What boats drop when they sink.
+    #[serde(default)]
+    pub loot: Vec<Loot>,
+    /// Score awarded for collecting a collectible, or for fully salvaging a wreck.
+    #[serde(default)]
+    pub value: u32,
+    /// Extra score awarded for collecting a collectible, by sub kind of the collector.
+    #[serde(default)]
+    pub value_bonus: HashMap<EntitySubKind, u32>,
+    /// Health restored by collecting a collectible.
+    #[serde(default)]
+    pub repair: Ticks,
+    /// Whether collecting a collectible puts out a stack of fire and flooding.
+    #[serde(default)]
+    pub extinguish: bool,
     pub label: String,
     #[serde(default)]
     pub position_forward: f32,
@@ -154,9 +195,19 @@ impl EntityData {
     /// Travelling at a speed (in mps) above this will cause more noise to be produced (12 knots).
     pub const CAVITATION_VELOCITY: f32 = 6.17333;
 
+    /// Vertical speed (in mps) of ballistic shells, which determines the height of their arc.
+    pub const BALLISTIC_VERTICAL_SPEED: f32 = 300.0;
+
+    /// Boats within this multiple of a ballistic shell's splash radius are revealed by the splash.
+    pub const BRACKET_FACTOR: f32 = 3.0;
+
     /// Constant used for checking whether a depth charge should explode.
     pub const DEPTH_CHARGE_PROXIMITY: f32 = 30.0;
 
+    /// Hulls are simplified to at most this many vertices by the sprite sheet packer, so that
+    /// collision checks can keep them on the stack.
+    pub const MAX_HULL_VERTICES: usize = 12;
+
     /// radii range of throttle (0-100%) and limit of collecting things.
     pub fn radii(&self) -> Range<f32> {
         self.length * 0.55..self.length
@@ -183,9 +234,16 @@ impl EntityData {
         self.sensors.visual.range * 0.75
     }
 
-    /// Range of anti aircraft guns (whereas `self.anti_aircraft` is their power).
-    pub fn anti_aircraft_range(&self) -> f32 {
-        self.radii().end
+    /// current_drift returns the fraction of ocean currents' velocity that the entity drifts with,
+    /// while floating.
+    pub fn current_drift(&self) -> f32 {
+        match (self.kind, self.sub_kind) {
+            (EntityKind::Collectible | EntityKind::Decoy, _) => 1.0,
+            (EntityKind::Weapon, EntitySubKind::Mine) => 1.0,
+            // Boats mostly overpower currents.
+            (EntityKind::Boat, _) => 0.2,
+            _ => 0.0,
+        }
     }
 
     /// returns whether this entity type primarily/only exists on land, as opposed to water.
@@ -193,6 +251,12 @@ impl EntityData {
         self.sub_kind == EntitySubKind::Tree
     }
 
+    /// is_ballistic returns whether this is a shell that arcs towards where it was aimed, landing
+    /// with a splash.
+    pub fn is_ballistic(&self) -> bool {
+        self.splash > 0.0
+    }
+
     /// max_health returns the the minimum damage to kill a boat, panicking if the corresponding
     /// entity does not have health.
     pub fn max_health(&self) -> Ticks {
@@ -210,9 +274,29 @@ impl EntityData {
         }
     }
 
-    /// armament_transform returns the entity-relative transform of a given armament.
-    pub fn armament_transform(&self, turret_angles: &[Angle], index: usize) -> Transform {
-        let armament = &self.armaments[index];
+    /// loadout_armaments returns the armaments of a given loadout, defaulting to the standard
+    /// armaments if it doesn't exist.
+    pub fn loadout_armaments(&self, loadout: u8) -> &[Armament] {
+        (loadout as usize)
+            .checked_sub(1)
+            .and_then(|i| self.loadouts.get(i))
+            .map_or(&self.armaments, |l| &l.armaments)
+    }
+
+    /// is_valid_loadout returns whether a loadout exists.
+    pub fn is_valid_loadout(&self, loadout: u8) -> bool {
+        loadout as usize <= self.loadouts.len()
+    }
+
+    /// armament_transform returns the entity-relative transform of a given armament of a given
+    /// loadout.
+    pub fn armament_transform(
+        &self,
+        loadout: u8,
+        turret_angles: &[Angle],
+        index: usize,
+    ) -> Transform {
+        let armament = &self.loadout_armaments(loadout)[index];
         let mut transform = Transform {
             position: armament.position(),
             direction: armament.angle,
@@ -320,6 +404,26 @@ impl Armament {
     }
 }
 
+/// An alternative set of armaments for the same hull.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Loadout {
+    pub label: String,
+    pub armaments: Vec<Armament>,
+}
+
+/// An entry in a loot table.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Loot {
+    #[serde(rename = "type")]
+    pub entity_type: EntityType,
+    /// Relative chance of this entry being chosen, per roll.
+    pub weight: f32,
+    /// How many entities this entry drops when chosen.
+    pub count: u8,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Exhaust {
@@ -338,6 +442,9 @@ pub struct Sensors {
     pub radar: Sensor,
     #[serde(default)]
     pub sonar: Sensor,
+    /// Electronic countermeasure that degrades enemy radar.
+    #[serde(default)]
+    pub jammer: Option<Jammer>,
 }
 
 impl Sensors {
@@ -533,30 +640,9 @@ impl EntityType {
         // Loot is based on the length of the boat.
         let loot_amount = (data.length * 0.25 * (rng.gen::<f32>() * 0.1 + 0.9)) as u32;
 
-        let mut loot_table = ArrayVec::<Self, 4>::new();
-
-        match data.sub_kind {
-            EntitySubKind::Pirate => {
-                loot_table.push(Self::Crate);
-                loot_table.push(Self::Coin);
-            }
-            EntitySubKind::Tanker => {
-                loot_table.push(Self::Scrap);
-                loot_table.push(Self::Barrel);
-            }
-            _ => match self {
-                Self::Olympias => loot_table.push(Self::Crate),
-                _ => loot_table.push(Self::Scrap),
-            },
-        };
-
         (0..loot_amount)
-            .map(move |_| {
-                *loot_table
-                    .iter()
-                    .choose(&mut rng)
-                    .expect("at least once loot table option")
-            })
+            .filter_map(move |_| data.loot.choose_weighted(&mut rng, |l| l.weight).ok())
+            .flat_map(|loot| iter::repeat(loot.entity_type).take(loot.count as usize))
             .chain((0..coin_amount).map(|_| Self::Coin))
     }
 
@@ -575,6 +661,16 @@ impl EntityType {
         vector.extend(sorted.into_iter().map(|(_, mut v)| {
             v.radius = Vec2::new(v.width, v.length).mul(0.5).length();
             v.inv_size = 1.0 / (v.radius * (1.0 / 30.0) * (1.0 - v.stealth).powi(2)).min(1.0);
+            if v.hull.is_empty() {
+                let half = v.dimensions() * 0.5;
+                v.hull = vec![
+                    Vec2::new(-half.x, -half.y),
+                    Vec2::new(half.x, -half.y),
+                    Vec2::new(half.x, half.y),
+                    Vec2::new(-half.x, half.y),
+                ];
+            }
+            assert!(v.hull.len() <= EntityData::MAX_HULL_VERTICES);
             v
         }));
 
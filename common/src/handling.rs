@@ -0,0 +1,156 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::angle::Angle;
+use crate::guidance::Guidance;
+use crate::transform::Transform;
+use crate::velocity::Velocity;
+use serde::{Deserialize, Serialize};
+
+/// Handling is how a ship responds to guidance, such that large ships have momentum and wide
+/// turning circles, whereas small ships are nimble.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Handling {
+    /// Rate (in meters per second squared) of speeding up.
+    pub acceleration: f32,
+    /// Rate (in meters per second squared) of slowing down, which is aided by drag.
+    pub deceleration: f32,
+    /// Radius (in meters) of the tightest turn at full speed. Turns are tighter at lower speeds.
+    pub turning_radius: f32,
+    /// Maximum reverse speed, and reverse acceleration, as a fraction of forward.
+    pub reverse: f32,
+    /// Fraction of speed lost per radian turned.
+    pub turn_drag: f32,
+}
+
+impl Handling {
+    /// Below this speed (in meters per second), the rudder has as much effect as at this speed, so
+    /// that stationary ships can still turn (slowly).
+    const STEERAGE_SPEED: f32 = 2.0;
+
+    /// turn_rate returns the maximum turn rate (in radians per second) at a given speed (in meters
+    /// per second), given the current maximum speed.
+    pub fn turn_rate(&self, speed: f32, max_speed: f32) -> f32 {
+        let speed = speed.abs();
+        let radius = self.turning_radius * (0.5 + 0.5 * (speed / max_speed.max(1.0)).min(1.0));
+        speed.max(Self::STEERAGE_SPEED) / radius
+    }
+
+    /// apply modifies a ship's transform according to guidance, given the current maximum speed
+    /// (in meters per second).
+    pub fn apply(
+        &self,
+        transform: &mut Transform,
+        guidance: Guidance,
+        max_speed: f32,
+        delta_seconds: f32,
+    ) {
+        let mut speed = transform.velocity.to_mps();
+
+        let turn_max = Angle::from_radians(
+            (self.turn_rate(speed, max_speed) * delta_seconds).clamp(0.0, std::f32::consts::PI),
+        );
+        let turn = (guidance.direction_target - transform.direction).clamp_magnitude(turn_max);
+        transform.direction += turn;
+
+        // Tight turns bleed off speed.
+        speed *= (1.0 - self.turn_drag * turn.abs().to_radians()).max(0.0);
+
+        let target = guidance
+            .velocity_target
+            .to_mps()
+            .clamp(-max_speed * self.reverse, max_speed);
+
+        // Speeding up (in either direction) relies on the engines, whereas slowing down (including
+        // before changing direction) is aided by drag.
+        let rate = if target.abs() > speed.abs() && target * speed >= 0.0 {
+            if target < 0.0 {
+                self.acceleration * self.reverse
+            } else {
+                self.acceleration
+            }
+        } else {
+            self.deceleration
+        };
+
+        let max_delta = rate * delta_seconds;
+        let delta = (target - speed).clamp(-max_delta, max_delta);
+        let mut velocity = Velocity::from_mps(speed + delta);
+
+        // Velocity is quantized, so make sure that gradual changes aren't lost entirely.
+        if velocity == Velocity::from_mps(speed)
+            && (target - speed).abs() >= Velocity::UNIT.to_mps()
+        {
+            velocity += if delta > 0.0 {
+                Velocity::UNIT
+            } else {
+                -Velocity::UNIT
+            };
+        }
+
+        transform.velocity = velocity;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::angle::Angle;
+    use crate::entity::EntityType;
+    use crate::guidance::Guidance;
+    use crate::transform::Transform;
+    use crate::velocity::Velocity;
+
+    #[test]
+    fn handling() {
+        unsafe {
+            EntityType::init();
+        }
+
+        let small = EntityType::Komar.data();
+        let large = EntityType::Montana.data();
+        let small_handling = small.handling.as_ref().unwrap();
+        let large_handling = large.handling.as_ref().unwrap();
+
+        // Large ships are heavy.
+        assert!(small_handling.acceleration > large_handling.acceleration);
+        assert!(
+            small_handling.turn_rate(small.speed.to_mps(), small.speed.to_mps())
+                > large_handling.turn_rate(large.speed.to_mps(), large.speed.to_mps())
+        );
+
+        let max_speed = large.speed.to_mps();
+        let ahead = |transform: &mut Transform, direction_target: Angle, seconds: f32| {
+            let guidance = Guidance {
+                direction_target,
+                velocity_target: large.speed,
+            };
+            for _ in 0..(seconds * 10.0) as usize {
+                large_handling.apply(transform, guidance, max_speed, 0.1);
+            }
+        };
+
+        // Momentum.
+        let mut transform = Transform::new();
+        ahead(&mut transform, Angle::ZERO, 1.0);
+        assert!(transform.velocity < large.speed * 0.5);
+        ahead(&mut transform, Angle::ZERO, 120.0);
+        assert_eq!(transform.velocity, large.speed);
+
+        // Tight turns lose speed.
+        ahead(&mut transform, Angle::PI_2, 5.0);
+        assert!(transform.velocity < large.speed);
+        assert!(transform.direction != Angle::ZERO);
+
+        // Reverse is slower.
+        let guidance = Guidance {
+            direction_target: transform.direction,
+            velocity_target: Velocity::from_mps(-max_speed),
+        };
+        for _ in 0..1200 {
+            large_handling.apply(&mut transform, guidance, max_speed, 0.1);
+        }
+        assert!(transform.velocity < Velocity::ZERO);
+        assert!(transform.velocity.abs() < large.speed * 0.5);
+    }
+}
@@ -0,0 +1,38 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+/// Harbor is a sheltered bay, placed by the server, in which boats repair and reload quickly but
+/// cannot use weapons.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Harbor {
+    pub position: Vec2,
+}
+
+impl Harbor {
+    /// Radius of the area, around the harbor's position, that the harbor covers.
+    pub const RADIUS: f32 = 300.0;
+
+    /// contains returns whether a position is within the harbor.
+    pub fn contains(&self, position: Vec2) -> bool {
+        self.position.distance_squared(position) <= Self::RADIUS.powi(2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::harbor::Harbor;
+    use glam::Vec2;
+
+    #[test]
+    fn contains() {
+        let harbor = Harbor {
+            position: Vec2::new(100.0, -50.0),
+        };
+        assert!(harbor.contains(harbor.position));
+        assert!(harbor.contains(harbor.position + Vec2::X * (Harbor::RADIUS - 1.0)));
+        assert!(!harbor.contains(harbor.position + Vec2::Y * (Harbor::RADIUS + 1.0)));
+    }
+}
@@ -0,0 +1,67 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::angle::Angle;
+use crate::transform::Transform;
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+/// Jammer is an electronic countermeasure, which degrades enemy radar within a cone while the
+/// boat's active sensors are on, at the cost of giving away the boat itself.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Jammer {
+    /// Maximum distance at which enemy radar is jammed.
+    pub range: f32,
+    /// Half-angle of the cone in which enemy radar is jammed.
+    pub arc: Angle,
+    /// Direction of the center of the cone, relative to the boat's direction.
+    #[serde(default)]
+    pub angle: Angle,
+}
+
+impl Jammer {
+    /// Upper bound of any jammer's range.
+    pub const MAX_RANGE: f32 = 2000.0;
+    /// Multiplier of the radar range of a jammed boat.
+    pub const RADAR_FACTOR: f32 = 0.5;
+    /// Uncertainty of a jammer to the boats it jams, which is enough to reveal that it is there,
+    /// but not what it is.
+    pub const REVEAL_UNCERTAINTY: f32 = 0.75;
+
+    /// covers returns whether a position is within the cone of a jammer with a given transform.
+    pub fn covers(&self, transform: &Transform, position: Vec2) -> bool {
+        let diff = position - transform.position;
+        diff.length_squared() <= self.range.powi(2)
+            && (Angle::from(diff) - (transform.direction + self.angle)).abs() <= self.arc
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::angle::Angle;
+    use crate::jammer::Jammer;
+    use crate::transform::Transform;
+    use crate::velocity::Velocity;
+    use glam::Vec2;
+
+    #[test]
+    fn covers() {
+        let jammer = Jammer {
+            range: 1000.0,
+            arc: Angle::from_degrees(30.0),
+            angle: Angle::ZERO,
+        };
+        let transform = Transform {
+            position: Vec2::new(100.0, 100.0),
+            direction: Angle::from_degrees(90.0),
+            velocity: Velocity::ZERO,
+        };
+
+        assert!(jammer.covers(&transform, Vec2::new(100.0, 600.0)));
+        assert!(jammer.covers(&transform, Vec2::new(300.0, 600.0)));
+        assert!(!jammer.covers(&transform, Vec2::new(100.0, 1200.0)));
+        assert!(!jammer.covers(&transform, Vec2::new(600.0, 100.0)));
+        assert!(!jammer.covers(&transform, Vec2::new(100.0, -400.0)));
+    }
+}
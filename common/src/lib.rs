@@ -7,14 +7,24 @@
 #![feature(result_into_ok_or_err)]
 #![feature(mixed_integer_ops)]
 
+pub mod affliction;
 pub mod altitude;
 pub mod angle;
+pub mod anti_aircraft;
 pub mod complete;
+pub mod consumable;
 pub mod contact;
+pub mod current;
 pub mod death_reason;
 pub mod entity;
 pub mod guidance;
+pub mod handling;
+pub mod harbor;
+pub mod jammer;
 pub mod protocol;
+pub mod seeker;
+pub mod storm;
+pub mod subsystems;
 pub mod terrain;
 pub mod ticks;
 pub mod transform;
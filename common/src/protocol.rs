@@ -2,11 +2,17 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use crate::altitude::Altitude;
+use crate::consumable::Consumable;
 use crate::contact::Contact;
+use crate::current::Currents;
 use crate::death_reason::DeathReason;
 use crate::entity::*;
 use crate::guidance::Guidance;
+use crate::harbor::Harbor;
+use crate::storm::Storm;
 use crate::terrain::{ChunkId, SerializedChunk};
+use crate::ticks::Ticks;
+use core_protocol::id::PlayerId;
 use glam::Vec2;
 use serde::{Deserialize, Serialize};
 
@@ -24,6 +30,65 @@ pub struct Update {
     /// Current world border radius.
     pub world_radius: f32,
     pub terrain: Box<TerrainUpdate>,
+    /// Anti-aircraft gunfire during the last tick, near the player's camera.
+    pub anti_aircraft: Vec<AntiAircraftFire>,
+    /// Ocean currents near the player's camera, sent occasionally because they vary slowly.
+    pub currents: Option<Currents>,
+    /// Harbors near the player's camera.
+    pub harbors: Vec<Harbor>,
+    /// Storm front, if any, wherever it is, so players can see what is limiting their sensors.
+    pub storm: Option<Storm>,
+    /// Things that happened to, or were done by, the player during the last tick.
+    pub events: Vec<Event>,
+}
+
+/// A discrete event involving the player, for the client to show as feedback (such as hit markers,
+/// damage numbers, and a kill feed).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum Event {
+    /// The player hit another boat with a weapon (or by ramming).
+    Hit {
+        target: EntityId,
+        victim: PlayerId,
+        entity_type: EntityType,
+        weapon_type: EntityType,
+        damage: Ticks,
+    },
+    /// The player's boat was hit by another player's weapon (or boat, if rammed).
+    HitBy {
+        attacker: PlayerId,
+        weapon_type: EntityType,
+        damage: Ticks,
+    },
+    /// The player sunk another boat.
+    Sunk {
+        victim: PlayerId,
+        entity_type: EntityType,
+        weapon_type: EntityType,
+        score: u32,
+    },
+    /// The player helped sink a boat that was sunk by another player, with their share of the
+    /// kill score.
+    Assist {
+        victim: PlayerId,
+        entity_type: EntityType,
+        /// Weapon (or boat, if ramming) that the player most recently damaged it with.
+        weapon_type: EntityType,
+        score: u32,
+    },
+    /// The player collected something, such as a coin or barrel.
+    Collected { entity_type: EntityType, score: u32 },
+    /// The player's boat was upgraded.
+    Upgraded(EntityType),
+    /// The player bought a consumable.
+    Bought(Consumable),
+}
+
+/// A boat's anti-aircraft guns firing at an aircraft, which the client can render as tracers.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AntiAircraftFire {
+    pub boat: EntityId,
+    pub aircraft: EntityId,
 }
 
 /// Updates for terrain chunks.
@@ -37,6 +102,7 @@ pub enum Command {
     Control(Control),
     Spawn(Spawn),
     Upgrade(Upgrade),
+    Buy(Buy),
 }
 
 /// Generic command to control one's ship.
@@ -61,7 +127,7 @@ pub struct Control {
 /// Fire/use a single weapon.
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct Fire {
-    /// The index of the weapon to fire/use, relative to `EntityData.armaments`.
+    /// The index of the weapon to fire/use, relative to the armaments of the boat's loadout.
     pub armament_index: u8,
 }
 
@@ -87,20 +153,35 @@ pub struct Pay;
 pub struct Spawn {
     /// What to spawn as. Must be a level 1 boat.
     pub entity_type: EntityType,
+    /// Which of the boat's loadouts to arm it with.
+    #[serde(default)]
+    pub loadout: u8,
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
 pub struct Upgrade {
     /// What to upgrade to. Must be affordable.
     pub entity_type: EntityType,
+    /// Which of the boat's loadouts to arm it with.
+    #[serde(default)]
+    pub loadout: u8,
+}
+
+/// Spend coins on a consumable.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct Buy {
+    /// What to buy. Must be affordable, and not already bought too many times this life.
+    pub consumable: Consumable,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::affliction::Afflictions;
     use crate::altitude::Altitude;
     use crate::entity::EntityId;
     use crate::guidance::Guidance;
+    use crate::subsystems::{Subsystem, Subsystems};
     use crate::ticks::Ticks;
     use crate::transform::Transform;
     use crate::velocity::Velocity;
@@ -125,8 +206,14 @@ mod tests {
                 .gen_bool(0.5)
                 .then(|| EntityType::iter().choose(&mut rng).unwrap());
             let is_boat = entity_type.map_or(false, |t| t.data().kind == EntityKind::Boat);
+            let loadout =
+                entity_type.map_or(0, |t| rng.gen_range(0..=t.data().loadouts.len() as u8));
 
             let c = Contact::new(
+                Afflictions {
+                    burning: is_boat && rng.gen_bool(0.25),
+                    flooding: is_boat && rng.gen_bool(0.25),
+                },
                 Altitude::from_u8(rng.gen()),
                 Ticks::from_secs(rng.gen::<f32>() * 10.0),
                 entity_type,
@@ -135,17 +222,30 @@ mod tests {
                     velocity_target: Velocity::from_mps(rng.gen::<f32>() * 3.0),
                 },
                 EntityId::new(rng.gen_range(1..u32::MAX)).unwrap(),
+                loadout,
                 rng.gen_bool(0.5)
                     .then(|| PlayerId(NonZeroU32::new(rng.gen_range(1..u32::MAX)).unwrap())),
                 (is_boat && rng.gen_bool(0.5)).then(|| {
                     entity_type
                         .unwrap()
                         .data()
-                        .armaments
+                        .loadout_armaments(loadout)
                         .iter()
                         .map(|_| Ticks::from_secs(rng.gen::<f32>() * 10.0))
                         .collect::<Arc<[Ticks]>>()
                 }),
+                (is_boat && rng.gen_bool(0.5)).then(|| {
+                    let mut subsystems = Subsystems::new(entity_type.unwrap(), loadout);
+                    subsystems.damage(
+                        Subsystem::Propulsion,
+                        Ticks::from_secs(rng.gen::<f32>() * 10.0),
+                    );
+                    subsystems.damage(
+                        Subsystem::Armament(0),
+                        Ticks::from_secs(rng.gen::<f32>() * 10.0),
+                    );
+                    subsystems
+                }),
                 Transform {
                     position: vec2(
                         rng.gen::<f32>() * 1000.0 - 500.0,
@@ -179,9 +279,9 @@ mod tests {
                     println!("len: {}, bytes: {:?}", bytes.len(), &bytes);
                     println!("contact: {:?}", &c);
 
-                    let byte = bytes[0];
-                    for i in 0u32..8 {
-                        println!("byte {}: {}", i, byte & (1 << i) != 0)
+                    let bits = u16::from_le_bytes([bytes[0], bytes[1]]);
+                    for i in 0..u16::BITS {
+                        println!("bit {}: {}", i, bits & (1 << i) != 0)
                     }
                     panic!("{}", err);
                 }
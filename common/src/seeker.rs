@@ -0,0 +1,125 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::angle::Angle;
+use crate::entity::{EntityData, EntityKind};
+use crate::ticks::Ticks;
+use serde::{Deserialize, Serialize};
+
+/// Seeker is how a homing weapon acquires and tracks targets.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Seeker {
+    /// Active seekers detect echoes (i.e. size), whereas passive seekers detect noise.
+    #[serde(default)]
+    pub active: bool,
+    /// Half-angle of the cone, centered on the weapon's direction, in which targets are detected.
+    pub arc: Angle,
+    /// Maximum distance at which targets are detected.
+    pub range: f32,
+    /// How long after launch the seeker starts searching.
+    #[serde(default)]
+    pub delay: Ticks,
+}
+
+impl Seeker {
+    /// Noise added by a boat using its own active sensors, which passive seekers can home in on.
+    const EMISSION_NOISE: f32 = 75.0;
+    /// Fraction of a decoy's noise that confuses active seekers.
+    const ACTIVE_DECOY_FACTOR: f32 = 0.5;
+
+    /// signature returns how much a target stands out to the seeker, given the target's speed (in
+    /// meters per second) and whether it is using active sensors.
+    pub fn signature(&self, target_data: &EntityData, speed: f32, emitting: bool) -> f32 {
+        if self.active {
+            // Echoes depend on size, and decoys only partially mask them.
+            target_data.radius + target_data.noise * Self::ACTIVE_DECOY_FACTOR
+        } else {
+            // Noise increases with speed, past the point of cavitation.
+            let cavitation = (speed - EntityData::CAVITATION_VELOCITY).max(0.0)
+                / EntityData::CAVITATION_VELOCITY;
+            let mut noise = target_data.radius * (1.0 + cavitation) + target_data.noise;
+            if emitting && target_data.kind == EntityKind::Boat {
+                noise += Self::EMISSION_NOISE;
+            }
+            noise
+        }
+    }
+
+    /// lock_strength returns how strongly the seeker would lock onto a target with a given
+    /// signature, at a given distance and angle (relative to the weapon's direction), or None if
+    /// the target is outside the seeker's cone. Only the strongest lock is followed.
+    pub fn lock_strength(&self, signature: f32, distance: f32, angle: Angle) -> Option<f32> {
+        if distance > self.range || angle.abs() > self.arc {
+            return None;
+        }
+        Some(
+            signature / EntityData::MAX_RADIUS
+                - (distance / self.range).powi(2)
+                - angle.abs().to_radians() / self.arc.to_radians(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::angle::Angle;
+    use crate::entity::EntityType;
+    use std::str::FromStr;
+
+    #[test]
+    fn decoys() {
+        unsafe {
+            EntityType::init();
+        }
+
+        let passive = EntityType::from_str("set65")
+            .unwrap()
+            .data()
+            .seeker
+            .as_ref()
+            .unwrap();
+        let active = EntityType::from_str("mark54")
+            .unwrap()
+            .data()
+            .seeker
+            .as_ref()
+            .unwrap();
+        assert!(!passive.active);
+        assert!(active.active);
+
+        let boat = EntityType::Fletcher.data();
+        let decoy = EntityType::from_str("mk70").unwrap().data();
+
+        // Decoys are more convincing to passive seekers, especially next to a quiet boat.
+        let passive_ratio =
+            passive.signature(decoy, 0.0, false) / passive.signature(boat, 0.0, false);
+        let active_ratio = active.signature(decoy, 0.0, false) / active.signature(boat, 0.0, false);
+        assert!(passive_ratio > active_ratio);
+        assert!(passive.signature(boat, 15.0, true) > passive.signature(boat, 0.0, false));
+
+        // Chaff stands out more than a large destroyer to a radar seeker.
+        let radar = EntityType::from_str("harpoon")
+            .unwrap()
+            .data()
+            .seeker
+            .as_ref()
+            .unwrap();
+        let destroyer = EntityType::from_str("arleighBurke").unwrap().data();
+        let chaff = EntityType::from_str("mk36").unwrap().data();
+        assert!(radar.active);
+        assert!(radar.signature(chaff, 0.0, false) > radar.signature(destroyer, 0.0, true));
+
+        // Targets outside the cone are ignored.
+        let signature = active.signature(boat, 0.0, false);
+        assert!(active
+            .lock_strength(signature, active.range * 0.5, Angle::ZERO)
+            .is_some());
+        assert!(active
+            .lock_strength(signature, active.range * 0.5, active.arc * 1.5)
+            .is_none());
+        assert!(active
+            .lock_strength(signature, active.range * 1.5, Angle::ZERO)
+            .is_none());
+    }
+}
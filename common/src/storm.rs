@@ -0,0 +1,45 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+/// A storm front, caused by a scheduled event, which boats' visual and radar sensors struggle to
+/// see out of.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Storm {
+    pub position: Vec2,
+    /// Meters per second.
+    pub velocity: Vec2,
+    pub radius: f32,
+}
+
+impl Storm {
+    /// Radius of new storms.
+    pub const RADIUS: f32 = 800.0;
+    /// Multiplier of the visual and radar range of boats in the storm.
+    pub const SENSOR_FACTOR: f32 = 0.5;
+
+    /// contains returns whether a position is in the storm.
+    pub fn contains(&self, position: Vec2) -> bool {
+        self.position.distance_squared(position) <= self.radius.powi(2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::storm::Storm;
+    use glam::Vec2;
+
+    #[test]
+    fn contains() {
+        let storm = Storm {
+            position: Vec2::new(-200.0, 300.0),
+            velocity: Vec2::X,
+            radius: Storm::RADIUS,
+        };
+        assert!(storm.contains(storm.position));
+        assert!(storm.contains(storm.position + Vec2::Y * (Storm::RADIUS - 1.0)));
+        assert!(!storm.contains(storm.position - Vec2::X * (Storm::RADIUS + 1.0)));
+    }
+}
@@ -0,0 +1,180 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::angle::Angle;
+use crate::entity::{EntityData, EntityType};
+use crate::ticks::Ticks;
+use crate::util::make_mut_slice;
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+use std::iter::repeat;
+use std::sync::Arc;
+
+/// Subsystem is a part of a boat that can be damaged separately from its hull.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Subsystem {
+    /// Engines and rudder, near the stern.
+    Propulsion,
+    /// Sensor masts, amidships.
+    Sensors,
+    /// A particular armament, by index.
+    Armament(usize),
+}
+
+/// Subsystems tracks damage to parts of a boat, in addition to its overall damage. Each part is
+/// repaired at the same rate as the boat as a whole.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Subsystems {
+    /// Damage to propulsion, which reduces max speed.
+    pub propulsion: Ticks,
+    /// Damage to sensors, which reduces visual and radar range.
+    pub sensors: Ticks,
+    /// Damage to each armament, which cannot be fired until repaired.
+    pub armaments: Arc<[Ticks]>,
+}
+
+impl Subsystems {
+    /// Maximum damage to any one subsystem, i.e. how long it takes to repair at the base rate.
+    pub const MAX_DAMAGE: Ticks = Ticks(Ticks::FREQUENCY_HZ.0 * 15);
+    /// Maximum reduction in speed or sensor range, at maximum damage.
+    const MAX_PENALTY: f32 = 0.5;
+
+    /// new allocates undamaged subsystems, sized to a particular entity type and loadout.
+    pub fn new(entity_type: EntityType, loadout: u8) -> Self {
+        Self {
+            propulsion: Ticks::ZERO,
+            sensors: Ticks::ZERO,
+            armaments: repeat(Ticks::ZERO)
+                .take(entity_type.data().loadout_armaments(loadout).len())
+                .collect(),
+        }
+    }
+
+    /// locate returns the subsystem, if any, at a position relative to the boat's center and
+    /// direction (i.e. positive x is towards the bow).
+    pub fn locate(
+        data: &EntityData,
+        loadout: u8,
+        turret_angles: &[Angle],
+        position: Vec2,
+    ) -> Option<Subsystem> {
+        // Armaments are small targets, so require a close hit.
+        let armament_radius_squared = (data.width * 0.4).powi(2);
+        let armament = (0..data.loadout_armaments(loadout).len())
+            .map(|i| {
+                let armament_position = data.armament_transform(loadout, turret_angles, i).position;
+                (i, armament_position.distance_squared(position))
+            })
+            .filter(|&(_, d2)| d2 < armament_radius_squared)
+            .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap());
+
+        let forward = position.x / data.length;
+        if let Some((i, _)) = armament {
+            Some(Subsystem::Armament(i))
+        } else if forward < -0.3 {
+            Some(Subsystem::Propulsion)
+        } else if forward.abs() < 0.15 {
+            Some(Subsystem::Sensors)
+        } else {
+            None
+        }
+    }
+
+    /// damage damages a subsystem by a certain amount, up to the maximum.
+    pub fn damage(&mut self, subsystem: Subsystem, amount: Ticks) {
+        let damage = match subsystem {
+            Subsystem::Propulsion => &mut self.propulsion,
+            Subsystem::Sensors => &mut self.sensors,
+            Subsystem::Armament(i) => match make_mut_slice(&mut self.armaments).get_mut(i) {
+                Some(damage) => damage,
+                None => return,
+            },
+        };
+        *damage = damage.saturating_add(amount).min(Self::MAX_DAMAGE);
+    }
+
+    /// repair repairs all subsystems by a certain amount.
+    pub fn repair(&mut self, amount: Ticks) {
+        self.propulsion = self.propulsion.saturating_sub(amount);
+        self.sensors = self.sensors.saturating_sub(amount);
+        if self.armaments.iter().any(|&d| d != Ticks::ZERO) {
+            for damage in make_mut_slice(&mut self.armaments) {
+                *damage = damage.saturating_sub(amount);
+            }
+        }
+    }
+
+    /// speed_factor returns the fraction of max speed available, given propulsion damage.
+    pub fn speed_factor(&self) -> f32 {
+        Self::factor(self.propulsion)
+    }
+
+    /// sensor_factor returns the fraction of visual and radar range available, given sensor damage.
+    pub fn sensor_factor(&self) -> f32 {
+        Self::factor(self.sensors)
+    }
+
+    /// is_armament_disabled returns whether an armament is too damaged to fire.
+    pub fn is_armament_disabled(&self, index: usize) -> bool {
+        self.armaments
+            .get(index)
+            .map_or(false, |&d| d != Ticks::ZERO)
+    }
+
+    fn factor(damage: Ticks) -> f32 {
+        1.0 - Self::MAX_PENALTY * damage.to_secs() / Self::MAX_DAMAGE.to_secs()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entity::EntityType;
+    use crate::subsystems::{Subsystem, Subsystems};
+    use crate::ticks::Ticks;
+    use glam::Vec2;
+
+    #[test]
+    fn subsystems() {
+        unsafe {
+            EntityType::init();
+        }
+
+        let entity_type = EntityType::Fletcher;
+        let data = entity_type.data();
+        let turret_angles: Vec<_> = data.turrets.iter().map(|t| t.angle).collect();
+        let mut subsystems = Subsystems::new(entity_type, 0);
+
+        assert_eq!(
+            Subsystems::locate(
+                data,
+                0,
+                &turret_angles,
+                Vec2::new(-0.4 * data.length, 0.45 * data.width)
+            ),
+            Some(Subsystem::Propulsion)
+        );
+        let armament_position = data.armament_transform(0, &turret_angles, 0).position;
+        assert_eq!(
+            Subsystems::locate(data, 0, &turret_angles, armament_position),
+            Some(Subsystem::Armament(0))
+        );
+
+        subsystems.damage(Subsystem::Propulsion, Ticks::MAX);
+        subsystems.damage(Subsystem::Armament(0), Ticks::from_secs(1.0));
+        assert_eq!(subsystems.propulsion, Subsystems::MAX_DAMAGE);
+        assert!(subsystems.speed_factor() < 1.0);
+        assert!(subsystems.is_armament_disabled(0));
+
+        subsystems.repair(Subsystems::MAX_DAMAGE);
+        assert_eq!(subsystems, Subsystems::new(entity_type, 0));
+        assert_eq!(subsystems.speed_factor(), 1.0);
+
+        // Sized to the chosen loadout.
+        assert!(data.is_valid_loadout(1) && !data.is_valid_loadout(2));
+        assert_eq!(
+            Subsystems::new(entity_type, 1).armaments.len(),
+            data.loadouts[0].armaments.len()
+        );
+    }
+}
@@ -536,6 +536,27 @@ impl Terrain {
         (max > Altitude::MIN).then_some((max_position, max))
     }
 
+    /// collides_with_segment returns the first point (and the altitude there) along a line segment,
+    /// from start to end, at which the terrain is at least threshold. Unlike collides_with, this is
+    /// suitable for thin, fast moving entities.
+    pub fn collides_with_segment(
+        &self,
+        start: Vec2,
+        end: Vec2,
+        threshold: Altitude,
+    ) -> Option<(Vec2, Altitude)> {
+        // Fine enough to not skip over corners of land.
+        const STEP: f32 = SCALE * 0.25;
+
+        let steps = (start.distance(end) * (1.0 / STEP)).ceil().max(1.0) as usize;
+        (0..=steps).find_map(|i| {
+            let pos = start.lerp(end, i as f32 / steps as f32);
+            self.sample(pos)
+                .filter(|&alt| alt >= threshold)
+                .map(|alt| (pos, alt))
+        })
+    }
+
     /// Returns if there is any land in a square, centered at center. Useful for determining whether something can spawn.
     pub fn land_in_square(&self, center: Vec2, side_length: f32) -> bool {
         let lower_left = Coord::saturating_from_position(center - side_length * 0.5);
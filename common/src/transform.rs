@@ -50,6 +50,11 @@ impl Transform {
         debug_assert!(delta_seconds >= 0.0);
         max_speed = max_speed.min(data.speed.to_mps());
 
+        if let Some(handling) = &data.handling {
+            handling.apply(self, guidance, max_speed, delta_seconds);
+            return;
+        }
+
         // Collectibles don't turn with guidance.
         // Shells and rockets (at least the ones currently in the game) can't turn.
         if data.kind != EntityKind::Collectible
@@ -120,6 +125,21 @@ impl Transform {
                     .clamp_length_max(keel_length * 0.5)
         }
     }
+
+    /// to_local converts a position to be relative to the transform's position and direction
+    /// (i.e. positive x is forward). This is the inverse of adding a relative transform.
+    pub fn to_local(&self, position: Vec2) -> Vec2 {
+        let normal = self.direction.to_vec();
+        let pos_diff = position - self.position;
+        Vec2::new(pos_diff.dot(normal), normal.perp_dot(pos_diff))
+    }
+
+    /// to_global converts a position relative to the transform's position and direction back to
+    /// an absolute position. This is the inverse of `to_local`.
+    pub fn to_global(&self, position: Vec2) -> Vec2 {
+        let normal = self.direction.to_vec();
+        self.position + normal * position.x + normal.perp() * position.y
+    }
 }
 
 impl Add for Transform {
@@ -97,6 +97,40 @@ pub fn ram_score(score: u32) -> u32 {
     kill_score(score) / 2
 }
 
+/// bounty_growth returns how much a wanted player's bounty grows by each second, which is faster
+/// for higher scores and longer kill streaks.
+pub fn bounty_growth(score: u32, kill_streak: u32) -> u32 {
+    1 + score / 2000 + kill_streak
+}
+
+/// max_bounty returns the most that a player's bounty can grow to, such that it at most triples
+/// the reward for killing them.
+pub fn max_bounty(score: u32) -> u32 {
+    kill_score(score) * 2
+}
+
+/// split_kill_score divides the score for a kill between the killer and any assists, given the
+/// damage each assist contributed. The killer gets 70%, and the assists share the rest in
+/// proportion to their damage. Without any assists, the killer gets everything. Returns the killer's
+/// score, followed by the score of each assist.
+pub fn split_kill_score(score: u32, assist_damages: &[f32]) -> (u32, Vec<u32>) {
+    const KILLER_SHARE: f32 = 0.7;
+
+    let total_damage: f32 = assist_damages.iter().sum();
+    if total_damage <= 0.0 {
+        return (score, vec![0; assist_damages.len()]);
+    }
+
+    let pool = score as f32 * (1.0 - KILLER_SHARE);
+    let assist_scores: Vec<u32> = assist_damages
+        .iter()
+        .map(|damage| (pool * damage / total_damage) as u32)
+        .collect();
+
+    // Any rounding error goes to the killer.
+    (score - assist_scores.iter().sum::<u32>(), assist_scores)
+}
+
 /// natural_death_coins returns how many coins a boat should drop, assuming it died of natural causes.
 pub fn natural_death_coins(score: u32) -> u32 {
     (score / 4 / 10).min(200)
@@ -137,6 +171,7 @@ mod test {
     use crate::entity::{EntityData, EntityKind, EntityType};
     use crate::util::{
         kill_score, level_to_score, lose_n_levels, ram_score, respawn_score, score_to_level,
+        split_kill_score,
     };
     use rand::seq::IteratorRandom;
     use rand::{thread_rng, Rng};
@@ -188,6 +223,18 @@ mod test {
          */
     }
 
+    #[test]
+    fn test_split_kill_score() {
+        assert_eq!(split_kill_score(100, &[]), (100, vec![]));
+        assert_eq!(split_kill_score(100, &[0.0]), (100, vec![0]));
+        assert_eq!(split_kill_score(100, &[1.0]), (70, vec![30]));
+        assert_eq!(split_kill_score(100, &[3.0, 1.0]), (71, vec![22, 7]));
+
+        // Score is conserved.
+        let (killer, assists) = split_kill_score(37, &[0.3, 0.5, 0.2]);
+        assert_eq!(killer + assists.iter().sum::<u32>(), 37);
+    }
+
     #[test]
     fn non_conservation_of_score() {
         unsafe {
@@ -223,10 +270,7 @@ mod test {
                 let mut winnings = boats[died]
                     .0
                     .loot(boats[died].1, natural)
-                    .map(|t| match t {
-                        EntityType::Coin => 10,
-                        _ => 2,
-                    })
+                    .map(|t| t.data().value)
                     .sum::<u32>();
 
                 if !natural {
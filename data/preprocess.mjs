@@ -29,6 +29,9 @@ function mapRanges(number, oldMin, oldMax, newMin, newMax, clampToRange = false)
 
 const entityDatas = JSON.parse(fs.readFileSync('./entities-raw.json'));
 
+// Normalized collision shapes traced from sprites by the sprite sheet packer.
+const hulls = fs.existsSync('./hulls.json') ? JSON.parse(fs.readFileSync('./hulls.json')) : {};
+
 for (const entityType of Object.keys(entityDatas)) {
 	const entityData = entityDatas[entityType];
 
@@ -98,14 +101,47 @@ for (const entityType of Object.keys(entityDatas)) {
 			entityData.limited = true;
 			break;
 		case 'boat':
-			// Anti-aircraft power
+			// Collision shape, in meters.
+			if (hulls[entityType]) {
+				entityData.hull = hulls[entityType].map(([x, y]) => [
+					parseFloat((x * entityData.length).toFixed(2)),
+					parseFloat((y * entityData.width).toFixed(2))
+				]);
+			}
+
+			// Handling, for boats that opt in with "handling": true (or an object of overrides),
+			// which is heavier for ships with more displacement.
+			if (entityData.handling) {
+				const displacement = Math.sqrt(entityData.length * entityData.width);
+				const acceleration = clamp(60 / displacement, 0.5, 6);
+				entityData.handling = {
+					acceleration: parseFloat(acceleration.toFixed(3)),
+					deceleration: parseFloat((acceleration * 1.5).toFixed(3)),
+					turningRadius: parseFloat((8 * Math.sqrt(entityData.length)).toFixed(1)),
+					reverse: 0.25,
+					// Hovercraft slide rather than plough through turns.
+					turnDrag: entityData.subkind === 'hovercraft' ? 0.2 : 0.8,
+					...(entityData.handling === true ? {} : entityData.handling)
+				};
+			}
+
+			// Anti-aircraft guns, which are blind towards the bow and stern.
 			switch (entityData.subkind) {
 				case 'dredger':
 				case 'submarine':
 				case 'tanker':
 					break;
 				default:
-					entityData.antiAircraft = parseFloat(mapRanges(entityData.length, 30, 300, 0.1, 0.5).toFixed(3));
+					const antiAircraft = {
+						power: parseFloat(mapRanges(entityData.length, 30, 300, 0.1, 0.5).toFixed(3)),
+						range: entityData.length,
+						arc: 75,
+						channels: Math.max(1, Math.round(entityData.length / 75)),
+						...entityData.antiAircraft
+					};
+					// Degrees to radians
+					antiAircraft.arc *= Math.PI / 180;
+					entityData.antiAircraft = antiAircraft;
 			}
 
 			// Torpedo resistance.
@@ -125,6 +161,19 @@ for (const entityType of Object.keys(entityDatas)) {
 					entityData.npc = true;
 					break;
 			}
+
+			// Loot (rolled once per 4 meters of length).
+			if (!Array.isArray(entityData.loot)) {
+				entityData.loot = [{type: 'scrap'}];
+			}
+			for (const loot of entityData.loot) {
+				if (typeof loot.weight !== 'number') {
+					loot.weight = 1;
+				}
+				if (typeof loot.count !== 'number') {
+					loot.count = 1;
+				}
+			}
 			break;
 	}
 
@@ -181,6 +230,12 @@ for (const entityType of Object.keys(entityDatas)) {
         entityData.damage *= damageMultiplier;
     }
 
+	// Shells that opt in with "splash": true are ballistic, landing where they are aimed with a
+	// splash radius that depends on their caliber.
+	if (entityData.splash === true) {
+		entityData.splash = mapRanges(entityData.width, 0.3, 0.5, 15, 30, true);
+	}
+
 	if (entityData.reload == undefined) {
 		switch (entityData.kind) {
 			case 'weapon':
@@ -230,9 +285,15 @@ for (const entityType of Object.keys(entityDatas)) {
 		}
 	}
 
-	const armaments = entityData.armaments;
+	const armaments = entityData.armaments || [];
 	entityData.armaments = [];
 
+	// Alternative armaments for the same hull, in place of the standard armaments (but not those
+	// belonging to typed turrets).
+	const loadouts = entityData.loadouts;
+	delete entityData.loadouts;
+	const turretArmaments = [];
+
 	const turrets = entityData.turrets;
 	entityData.turrets = [];
 
@@ -304,13 +365,14 @@ for (const entityType of Object.keys(entityDatas)) {
 
 			if (turret.type) {
 				for (const armament of entityDatas[turret.type].armaments) {
-					armaments.push({...armament, turret: i});
+					turretArmaments.push({...armament, turret: i});
 				}
 			}
 		}
 	}
 
-	if (armaments) {
+	function expandArmaments(armaments) {
+		const expanded = [];
 		for (const armament of armaments) {
 			if (typeof armament.angle === 'number') {
 				armament.angle *= Math.PI / 180;
@@ -319,16 +381,39 @@ for (const entityType of Object.keys(entityDatas)) {
 			delete armament.symmetrical;
 
 			for (let i = 0; i < (armament.count || 1); i++) {
-				entityData.armaments.push({...armament, count: undefined});
+				expanded.push({...armament, count: undefined});
 				if (sym) {
 					const copy = {...armament, positionSide: -armament.positionSide, count: undefined};
 					if (armament.angle != undefined) {
 						copy.angle = -armament.angle;
 					}
-					entityData.armaments.push(copy);
+					expanded.push(copy);
 				}
 			}
 		}
+		return expanded;
+	}
+
+	entityData.armaments = expandArmaments([...armaments, ...turretArmaments.map(a => ({...a}))]);
+
+	if (loadouts) {
+		if (entityData.kind !== 'boat') {
+			throw new Error(`${entityType}: only boats may have loadouts`);
+		}
+		entityData.loadouts = loadouts.map(loadout => {
+			if (!loadout.label || !loadout.armaments) {
+				throw new Error(`${entityType}: loadouts must have a label and armaments`);
+			}
+			for (const armament of loadout.armaments) {
+				if (armament.turret != undefined && !entityData.turrets[armament.turret]) {
+					throw new Error(`${entityType}: loadout ${loadout.label} has an armament on a nonexistent turret`);
+				}
+			}
+			return {
+				label: loadout.label,
+				armaments: expandArmaments([...loadout.armaments, ...turretArmaments.map(a => ({...a}))]),
+			};
+		});
 	}
 
 	if (exhausts) {
@@ -348,6 +433,10 @@ for (const entityType of Object.keys(entityDatas)) {
 		for (const sensorType in entityData.sensors) {
 			const sensor = entityData.sensors[sensorType];
 
+			if (sensorType === 'jammer') {
+				continue;
+			}
+
 			if (typeof sensor.range !== 'number') {
 				let base = 0;
 				let factor = 0;
@@ -375,6 +464,32 @@ for (const entityType of Object.keys(entityDatas)) {
 				sensor.range = Math.min(sensor.range, 2000);
 			}
 		}
+
+		// Jammers cover a cone, and default to the range of the radar they are paired with.
+		const jammer = entityData.sensors.jammer;
+		if (jammer) {
+			if (typeof jammer.range !== 'number') {
+				jammer.range = entityData.sensors.radar ? entityData.sensors.radar.range : 1000;
+			}
+			jammer.range = Math.min(jammer.range, 2000);
+			// Degrees to radians
+			jammer.arc = (jammer.arc || 45) * Math.PI / 180;
+			jammer.angle = (jammer.angle || 0) * Math.PI / 180;
+		}
+	}
+
+	// Homing weapons acquire targets with a seeker, which defaults to the range of their sensors.
+	if (['weapon', 'aircraft'].includes(entityData.kind) && entityData.sensors && !['rocket', 'rocketTorpedo'].includes(entityData.subkind)) {
+		const seeker = {
+			active: entityData.subkind !== 'torpedo',
+			arc: ['missile', 'torpedo'].includes(entityData.subkind) ? 45 : 80,
+			range: Math.max(...Object.values(entityData.sensors).map(sensor => sensor.range)),
+			delay: 1,
+			...entityData.seeker
+		};
+		// Degrees to radians
+		seeker.arc *= Math.PI / 180;
+		entityData.seeker = seeker;
 	}
 }
 
@@ -408,9 +523,32 @@ for (const entityType of Object.keys(entityDatas)) {
 		return 0;
 	}
 
-	entityData.armaments.sort((first, second) => {
-		return rankArmament(second) - rankArmament(first);
-	});
+	function sortArmaments(armaments) {
+		armaments.sort((first, second) => {
+			return rankArmament(second) - rankArmament(first);
+		});
+	}
+
+	sortArmaments(entityData.armaments);
+	for (const loadout of entityData.loadouts || []) {
+		sortArmaments(loadout.armaments);
+	}
 }
 
 fs.writeFileSync('../js/src/data/entities.json', JSON.stringify(entityDatas, null, '\t'));
+
+// Consumables, which players buy with coins, need only be validated.
+const consumables = JSON.parse(fs.readFileSync('./consumables.json'));
+
+for (const consumable of Object.keys(consumables)) {
+	const consumableData = consumables[consumable];
+
+	if (!(consumableData.cost > 0) || !(consumableData.limit > 0)) {
+		throw new Error(`consumable ${consumable} must have a positive cost and limit`);
+	}
+	if (consumableData.duration === undefined && (consumableData.sensorFactor || consumableData.visibilityFactor)) {
+		throw new Error(`consumable ${consumable} has a lasting effect without a duration`);
+	}
+}
+
+fs.writeFileSync('../js/src/data/consumables.json', JSON.stringify(consumables, null, '\t'));
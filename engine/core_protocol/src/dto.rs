@@ -8,6 +8,52 @@ use crate::UnixTime;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 
+/// The Bot Policy Data Transfer Object (DTO) controls the population of bots in an arena.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct BotPolicyDto {
+    /// Bots are added until there are this many, unless overridden by hourly_min_players.
+    pub min_players: usize,
+    /// Overrides min_players, indexed by UTC hour. Must have 24 elements to take effect.
+    pub hourly_min_players: Option<Vec<usize>>,
+    /// Bots, as a percentage of real players, to have regardless of min_players.
+    pub bot_percent: usize,
+    /// Each real player reduces the min_players bots by this percentage of a bot.
+    pub displacement_percent: usize,
+    /// Never have more than this many bots.
+    pub max_bots: usize,
+    /// New bots aim for levels similar to those of real players.
+    pub match_levels: bool,
+}
+
+impl BotPolicyDto {
+    /// new returns the legacy policy of `max(min_players, 80% of real players)` bots.
+    pub fn new(min_players: usize) -> Self {
+        Self {
+            min_players,
+            hourly_min_players: None,
+            bot_percent: if min_players == 0 { 0 } else { 80 },
+            displacement_percent: 0,
+            max_bots: usize::MAX,
+            match_levels: false,
+        }
+    }
+
+    /// bot_count returns how many bots there should be, given the number of real players and the
+    /// current UTC hour.
+    pub fn bot_count(&self, players: usize, hour: usize) -> usize {
+        let min_players = self
+            .hourly_min_players
+            .as_ref()
+            .filter(|hourly| hourly.len() == 24)
+            .map(|hourly| hourly[hour % 24])
+            .unwrap_or(self.min_players);
+        let displaced = min_players.saturating_sub(players * self.displacement_percent / 100);
+        displaced
+            .max(self.bot_percent * players / 100)
+            .min(self.max_bots)
+    }
+}
+
 /// The Survey Data Transfer Object (DTO) collects user feedback.
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct SurveyDto {
@@ -32,6 +78,9 @@ pub struct LeaderboardDto {
 pub struct LiveboardDto {
     pub player_id: PlayerId,
     pub score: u32,
+    /// Extra score for whoever kills the player, if any.
+    #[serde(default)]
+    pub bounty: u32,
     pub team_captain: bool,
     pub team_id: Option<TeamId>,
 }
@@ -50,6 +99,7 @@ impl Ord for LiveboardDto {
                 self.team_id
                     .cmp(&other.team_id)
                     .then_with(|| self.team_captain.cmp(&other.team_captain))
+                    .then_with(|| self.bounty.cmp(&other.bounty))
             })
         })
     }
@@ -57,21 +107,75 @@ impl Ord for LiveboardDto {
 
 #[cfg(test)]
 mod test {
-    use crate::dto::LiveboardDto;
+    use crate::dto::{BotPolicyDto, LiveboardDto};
     use crate::id::{PlayerId, TeamId};
     use std::num::NonZeroU32;
 
+    #[test]
+    fn bot_count_hourly() {
+        let mut hourly = vec![10; 24];
+        hourly[3] = 2;
+        let policy = BotPolicyDto {
+            hourly_min_players: Some(hourly),
+            ..BotPolicyDto::new(5)
+        };
+        assert_eq!(policy.bot_count(0, 0), 10);
+        assert_eq!(policy.bot_count(0, 3), 2);
+        assert_eq!(policy.bot_count(0, 27), 2);
+
+        // Wrong length is ignored.
+        let policy = BotPolicyDto {
+            hourly_min_players: Some(vec![10; 12]),
+            ..BotPolicyDto::new(5)
+        };
+        assert_eq!(policy.bot_count(0, 3), 5);
+    }
+
+    #[test]
+    fn bot_count_displacement() {
+        let policy = BotPolicyDto {
+            bot_percent: 0,
+            displacement_percent: 50,
+            ..BotPolicyDto::new(10)
+        };
+        assert_eq!(policy.bot_count(0, 0), 10);
+        assert_eq!(policy.bot_count(4, 0), 8);
+        assert_eq!(policy.bot_count(10, 0), 5);
+        assert_eq!(policy.bot_count(30, 0), 0);
+
+        // Bot percent still applies to displaced bots.
+        let policy = BotPolicyDto {
+            bot_percent: 20,
+            displacement_percent: 100,
+            ..BotPolicyDto::new(10)
+        };
+        assert_eq!(policy.bot_count(20, 0), 4);
+    }
+
+    #[test]
+    fn bot_count_max_bots() {
+        let policy = BotPolicyDto {
+            max_bots: 6,
+            ..BotPolicyDto::new(10)
+        };
+        assert_eq!(policy.bot_count(0, 0), 6);
+        assert_eq!(policy.bot_count(100, 0), 6);
+        assert_eq!(BotPolicyDto::new(10).bot_count(100, 0), 80);
+    }
+
     #[test]
     fn sort_order() {
         assert!(
             LiveboardDto {
                 player_id: PlayerId(NonZeroU32::new(2).unwrap()),
                 score: 5,
+                bounty: 0,
                 team_captain: true,
                 team_id: Some(TeamId(NonZeroU32::new(1).unwrap())),
             } < LiveboardDto {
                 player_id: PlayerId(NonZeroU32::new(1).unwrap()),
                 score: 3,
+                bounty: 0,
                 team_captain: false,
                 team_id: None,
             }
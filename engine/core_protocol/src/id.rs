@@ -126,6 +126,22 @@ impl PlayerId {
             .filter(|id| id.is_bot())
     }
 
+    /// How many ids, at the end of the range of bot ids, are reserved for non-player characters.
+    pub const NPC_COUNT: usize = 1024;
+
+    /// Gets the nth id associated with non-player characters. These are bot ids that the bot zoo
+    /// will never reach.
+    pub fn nth_npc(n: usize) -> Option<Self> {
+        (n < Self::NPC_COUNT)
+            .then(|| Self::nth_bot(Self::RANDOM_MASK as usize - 2 - n))
+            .flatten()
+    }
+
+    /// Returns true if the id is reserved for non-player characters.
+    pub fn is_npc(self) -> bool {
+        self.is_bot() && self.0.get() as usize > Self::RANDOM_MASK as usize - Self::NPC_COUNT
+    }
+
     /// Returns true if the id is reserved for bots.
     pub fn is_bot(self) -> bool {
         let n = self.0.get();
@@ -205,4 +221,15 @@ mod tests {
     fn solo() {
         assert!(PlayerId::SOLO_OFFLINE.is_solo());
     }
+
+    #[test]
+    fn npc() {
+        for n in 0..PlayerId::NPC_COUNT {
+            let id = PlayerId::nth_npc(n).unwrap();
+            assert!(id.is_bot());
+            assert!(id.is_npc());
+        }
+        assert!(PlayerId::nth_npc(PlayerId::NPC_COUNT).is_none());
+        assert!(!PlayerId::nth_bot(0).unwrap().is_npc());
+    }
 }
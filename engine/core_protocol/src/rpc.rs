@@ -48,6 +48,11 @@ pub enum AdminRequest {
     SetRedirect {
         server_id: Option<ServerId>,
     },
+    SetBotPolicy {
+        // If None, goes to all arenas.
+        arena_id: Option<ArenaId>,
+        policy: BotPolicyDto,
+    },
 }
 
 // Client requests are from the browser to the core service.
@@ -111,12 +116,25 @@ pub enum ServerRequest {
     DropSession {
         session_id: SessionId,
     },
+    // Bots don't have sessions, so they chat on behalf of their player id.
+    SendBotChat {
+        player_id: PlayerId,
+        message: String,
+    },
+    // Game servers announce things to the whole arena, such as bounties. Each "{n}" in the
+    // message is replaced by the alias of the n-th player.
+    SendServerChat {
+        message: String,
+        players: Vec<PlayerId>,
+    },
     SetStatus {
         session_id: SessionId,
         #[serde(default)]
         location: Option<Location>,
         #[serde(default)]
         score: Option<u32>,
+        #[serde(default)]
+        bounty: Option<u32>,
     },
     StartArena {
         game_id: GameId,
@@ -147,6 +165,7 @@ pub enum ServerRequest {
 #[cfg_attr(feature = "client", rtype(result = "()"))]
 #[derive(Debug, Serialize, Deserialize)]
 pub enum AdminUpdate {
+    BotPolicySet,
     ChatSent {
         sent: bool,
     },
@@ -277,6 +296,10 @@ pub enum ServerUpdate {
     ArmageddonStarted {
         arena_id: ArenaId,
     },
+    BotChatSent,
+    BotPolicyChanged {
+        policy: BotPolicyDto,
+    },
     MembersChanged {
         changes: Arc<[MemberDto]>,
     },
@@ -286,6 +309,7 @@ pub enum ServerUpdate {
     },
     PlayStopped,
     SessionDropped,
+    ServerChatSent,
     SessionValid {
         elapsed: u32,
         player_id: PlayerId,
@@ -250,6 +250,10 @@ impl Repo {
                     Err("unable to set redirect")
                 }
             }
+            AdminRequest::SetBotPolicy { arena_id, policy } => {
+                self.bot_policies.push((arena_id, policy));
+                result = Ok(AdminUpdate::BotPolicySet);
+            }
             _ => result = Err("cannot process admin request synchronously"),
         }
 
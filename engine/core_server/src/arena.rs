@@ -25,6 +25,8 @@ pub struct Arena {
     pub armageddon: bool,
     pub broadcast_players: NotifySet<SessionId>,
     pub broadcast_teams: NotifySet<TeamId>,
+    /// Bots have no session, so their chat contexts (for rate limiting) are kept here.
+    pub bot_chat_contexts: HashMap<PlayerId, rustrict::Context>,
     pub confide_membership: HashMap<PlayerId, Option<TeamId>>, // For game server.
     pub date_created: UnixTime,
     pub date_put: UnixTime,
@@ -59,6 +61,7 @@ impl Arena {
             armageddon: false,
             broadcast_players: NotifySet::new(),
             broadcast_teams: NotifySet::new(),
+            bot_chat_contexts: HashMap::new(),
             confide_membership: HashMap::new(),
             date_created,
             date_put: date_created,
@@ -145,6 +148,7 @@ impl Arena {
                         team_id: play.team_id,
                         player_id: session.player_id,
                         score,
+                        bounty: play.bounty.unwrap_or(0),
                     })
                 })
             } else {
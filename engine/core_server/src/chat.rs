@@ -7,10 +7,13 @@ use crate::session::Session;
 use crate::team::Team;
 use core_protocol::dto::MessageDto;
 use core_protocol::get_unix_time_now;
-use core_protocol::id::{ArenaId, GameId, PlayerId, SessionId};
+use core_protocol::id::{ArenaId, GameId, PlayerId, SessionId, TeamId};
 use core_protocol::name::{PlayerAlias, TeamName};
 use log::{debug, error, warn};
-use rustrict::{trim_whitespace, BlockReason, ContextProcessingOptions, ContextRateLimitOptions};
+use rustrict::{
+    trim_whitespace, BlockReason, CensorStr, Context, ContextProcessingOptions,
+    ContextRateLimitOptions,
+};
 use std::fs::OpenOptions;
 use std::rc::Rc;
 
@@ -124,19 +127,7 @@ impl Repo {
                     }
                     let trimmed = trim_whitespace(&message);
                     if play.date_stop.is_none() && !trimmed.is_empty() && trimmed.len() < 150 {
-                        let options = ContextProcessingOptions {
-                            rate_limit: if whisper {
-                                None
-                            } else {
-                                Some(ContextRateLimitOptions::default())
-                            },
-                            ..Default::default()
-                        };
-
-                        match session
-                            .chat_context
-                            .process_with_options(message.to_owned(), &options)
-                        {
+                        match process_chat(&mut session.chat_context, &message, whisper) {
                             Ok(text) => {
                                 let message = MessageDto {
                                     alias: session.alias,
@@ -171,38 +162,8 @@ impl Repo {
             } // sessions.get_mut
 
             if let Some((maybe_team_id, message)) = maybe_message_tuple {
-                if whisper {
-                    if let Some(whisper_team_id) = maybe_team_id {
-                        for (_, session) in Session::iter_mut(&mut arena.sessions) {
-                            // Must be live to receive whisper, otherwise your team affiliation isn't real.
-                            if !session.live
-                                || message
-                                    .player_id
-                                    .map(|id| session.muted.contains(&id))
-                                    .unwrap_or(false)
-                            {
-                                continue;
-                            }
-                            if let Some(play) = session.plays.last_mut() {
-                                if play.team_id == Some(whisper_team_id) {
-                                    session.inbox.write(Rc::clone(&message));
-                                    result = Some(Ok(session.player_id));
-                                }
-                            }
-                        }
-                    }
-                } else {
-                    for (_, session) in Session::iter_mut(&mut arena.sessions) {
-                        if !message
-                            .player_id
-                            .map(|id| session.muted.contains(&id))
-                            .unwrap_or(false)
-                        {
-                            session.inbox.write(Rc::clone(&message));
-                            result = Some(Ok(session.player_id));
-                        }
-                    }
-                    arena.newbie_messages.write(Rc::clone(&message));
+                if let Some(recipient) = broadcast_chat(arena, message, maybe_team_id) {
+                    result = Some(Ok(recipient));
                 }
             }
         }
@@ -215,6 +176,42 @@ impl Repo {
         result
     }
 
+    /// Bots send chats under their bot alias, filtered and rate limited like those of players.
+    pub fn bot_send_chat(&mut self, arena_id: ArenaId, player_id: PlayerId, message: &str) -> bool {
+        debug!(
+            "bot_send_chat(arena={:?}, player={:?}): {}",
+            arena_id, player_id, &message
+        );
+        let mut sent = false;
+        if let Some(arena) = Arena::get_mut(&mut self.arenas, arena_id) {
+            if player_id.is_bot() {
+                let context = arena.bot_chat_contexts.entry(player_id).or_default();
+
+                if let Some(text) = process_bot_chat(context, message) {
+                    let message = Rc::new(MessageDto {
+                        alias: PlayerAlias::from_bot_player_id(player_id),
+                        date_sent: get_unix_time_now(),
+                        player_id: Some(player_id),
+                        team_captain: false,
+                        team_name: None,
+                        text,
+                        whisper: false,
+                    });
+
+                    broadcast_chat(arena, message, None);
+                    sent = true;
+                }
+            }
+        }
+        if !sent {
+            warn!(
+                "bot_send_chat(arena={:?}, player={:?}) failed: {}",
+                arena_id, player_id, &message
+            );
+        }
+        sent
+    }
+
     /// Admins can send chats from any alias (although no PlayerId).
     pub fn admin_send_chat(
         &mut self,
@@ -240,11 +237,7 @@ impl Repo {
                 whisper: false,
             });
 
-            for (_, session) in Session::iter_mut(&mut arena.sessions) {
-                session.inbox.write(Rc::clone(&message));
-                sent = true;
-            }
-            arena.newbie_messages.write(Rc::clone(&message));
+            sent = broadcast_chat(arena, message, None).is_some();
         }
         if !sent {
             warn!(
@@ -254,6 +247,135 @@ impl Repo {
         }
         sent
     }
+
+    /// Game servers send chats, such as announcements, under the server's alias. Each "{n}" in the
+    /// message is replaced by the alias of the n-th player.
+    pub fn server_send_chat(
+        &mut self,
+        arena_id: ArenaId,
+        message: &str,
+        players: &[PlayerId],
+    ) -> bool {
+        debug!("server_send_chat(arena={:?}): {}", arena_id, &message);
+        let text = if let Some(arena) = Arena::get(&self.arenas, arena_id) {
+            let aliases: Vec<PlayerAlias> = players
+                .iter()
+                .map(|&player_id| {
+                    if player_id.is_bot() {
+                        PlayerAlias::from_bot_player_id(player_id)
+                    } else {
+                        arena
+                            .sessions
+                            .values()
+                            .find(|session| session.player_id == player_id)
+                            .map(|session| session.alias)
+                            .unwrap_or_default()
+                    }
+                })
+                .collect();
+            substitute_aliases(message, &aliases)
+        } else {
+            return false;
+        };
+
+        self.admin_send_chat(arena_id, PlayerAlias::new("Server"), &text)
+    }
+}
+
+/// Replaces each "{n}" in a template with the n-th alias, in a single pass, so that aliases that
+/// look like placeholders are left alone. Placeholders without a corresponding alias are kept.
+fn substitute_aliases(template: &str, aliases: &[PlayerAlias]) -> String {
+    let mut text = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        text.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let alias = rest
+            .find('}')
+            .and_then(|end| Some((end, rest[1..end].parse::<usize>().ok()?)))
+            .and_then(|(end, i)| Some((end, aliases.get(i)?)));
+        if let Some((end, alias)) = alias {
+            text.push_str(alias.as_str());
+            rest = &rest[end + 1..];
+        } else {
+            text.push('{');
+            rest = &rest[1..];
+        }
+    }
+    text.push_str(rest);
+    text
+}
+
+/// Filters a chat message, rate limiting it unless it is a whisper.
+fn process_chat(
+    context: &mut Context,
+    message: &str,
+    whisper: bool,
+) -> Result<String, BlockReason> {
+    let options = ContextProcessingOptions {
+        rate_limit: if whisper {
+            None
+        } else {
+            Some(ContextRateLimitOptions::default())
+        },
+        ..Default::default()
+    };
+
+    context.process_with_options(message.to_owned(), &options)
+}
+
+/// Filters a chat message from a bot, returning the text to send, if any. Bots have no business
+/// saying anything inappropriate, so such messages are rejected rather than censored.
+fn process_bot_chat(context: &mut Context, message: &str) -> Option<String> {
+    let trimmed = trim_whitespace(message);
+    if trimmed.is_empty() || trimmed.len() >= 150 || trimmed.is_inappropriate() {
+        return None;
+    }
+    process_chat(context, trimmed, false).ok()
+}
+
+/// Delivers a message to every session in the arena that hasn't muted the sender, or, if the
+/// message is a whisper, only to live members of the team. Returns the last recipient, if any.
+fn broadcast_chat(
+    arena: &mut Arena,
+    message: Rc<MessageDto>,
+    whisper_team_id: Option<TeamId>,
+) -> Option<PlayerId> {
+    let mut recipient = None;
+    if message.whisper {
+        if let Some(whisper_team_id) = whisper_team_id {
+            for (_, session) in Session::iter_mut(&mut arena.sessions) {
+                // Must be live to receive whisper, otherwise your team affiliation isn't real.
+                if !session.live
+                    || message
+                        .player_id
+                        .map(|id| session.muted.contains(&id))
+                        .unwrap_or(false)
+                {
+                    continue;
+                }
+                if let Some(play) = session.plays.last_mut() {
+                    if play.team_id == Some(whisper_team_id) {
+                        session.inbox.write(Rc::clone(&message));
+                        recipient = Some(session.player_id);
+                    }
+                }
+            }
+        }
+    } else {
+        for (_, session) in Session::iter_mut(&mut arena.sessions) {
+            if !message
+                .player_id
+                .map(|id| session.muted.contains(&id))
+                .unwrap_or(false)
+            {
+                session.inbox.write(Rc::clone(&message));
+                recipient = Some(session.player_id);
+            }
+        }
+        arena.newbie_messages.write(Rc::clone(&message));
+    }
+    recipient
 }
 
 /// Logs a chat message to a file.
@@ -286,3 +408,47 @@ pub fn log_chat(
         Err(e) => error!("Error logging chat: {:?}", e),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::chat::{process_bot_chat, substitute_aliases};
+    use core_protocol::name::PlayerAlias;
+    use rustrict::Context;
+
+    #[test]
+    fn substitute() {
+        let aliases = [PlayerAlias::new("{1}"), PlayerAlias::new("Bob")];
+        assert_eq!(
+            substitute_aliases("{0} sank {1} {2} {x}{", &aliases),
+            "{1} sank Bob {2} {x}{"
+        );
+    }
+
+    #[test]
+    fn bot_chat_filter() {
+        let mut context = Context::default();
+        assert_eq!(process_bot_chat(&mut context, "  "), None);
+        assert_eq!(process_bot_chat(&mut context, "fuck you"), None);
+        assert_eq!(process_bot_chat(&mut context, &"a".repeat(150)), None);
+        assert_eq!(
+            process_bot_chat(&mut context, " Hello there! "),
+            Some(String::from("Hello there!"))
+        );
+    }
+
+    #[test]
+    fn bot_chat_rate_limit() {
+        let mut context = Context::default();
+        assert!(process_bot_chat(&mut context, "Good game").is_some());
+        let lines = [
+            "Nice shot",
+            "Watch out",
+            "Help me",
+            "Over here",
+            "Follow me",
+        ];
+        assert!(lines
+            .iter()
+            .any(|line| process_bot_chat(&mut context, line).is_none()));
+    }
+}
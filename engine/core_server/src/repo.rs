@@ -10,7 +10,8 @@ use crate::health::Health;
 use chrono::Timelike;
 use chrono::Utc;
 use core_protocol::dto::{
-    LeaderboardDto, LiveboardDto, MemberDto, MessageDto, PlayerDto, RegionDto, RestartDto, TeamDto,
+    BotPolicyDto, LeaderboardDto, LiveboardDto, MemberDto, MessageDto, PlayerDto, RegionDto,
+    RestartDto, TeamDto,
 };
 use core_protocol::id::PeriodId;
 use core_protocol::id::*;
@@ -24,6 +25,8 @@ pub struct Repo {
     // Assume these fields are synchronized via Actor so Mutex is not required.
     pub arenas: HashMap<ArenaId, Arena>,
     pub armageddon: bool,
+    /// Bot policies waiting to be sent to servers, and the arena they apply to (or all if None).
+    pub bot_policies: Vec<(Option<ArenaId>, BotPolicyDto)>,
     pub invitations: HashMap<InvitationId, Invitation>,
     pub players: HashMap<PlayerId, SessionId>,
     pub prior_liveboard: Vec<LiveboardDto>,
@@ -45,6 +48,7 @@ impl Repo {
         Repo {
             arenas: HashMap::new(),
             armageddon: false,
+            bot_policies: Vec::new(),
             invitations: HashMap::new(),
             players: HashMap::new(),
             prior_liveboard: Vec::new(),
@@ -233,6 +237,11 @@ impl Repo {
         armageddon
     }
 
+    /// Assume caller polls this method to send bot policies to servers.
+    pub fn read_bot_policies(&mut self) -> Vec<(Option<ArenaId>, BotPolicyDto)> {
+        mem::take(&mut self.bot_policies)
+    }
+
     // Assume caller reads public updates and broadcasts to all clients.
     #[allow(clippy::type_complexity)]
     pub fn read_broadcasts(
@@ -98,6 +98,21 @@ impl Core {
                 }
             }
 
+            // Notify servers of bot policy changes.
+            for (policy_arena_id, policy) in act.repo.read_bot_policies() {
+                for (addr, server) in act.servers.iter() {
+                    if let Some(arena_id) = server.arena_id {
+                        if policy_arena_id.map(|id| id == arena_id).unwrap_or(true) {
+                            log_err(addr.do_send(ObserverUpdate::Send {
+                                message: ServerUpdate::BotPolicyChanged {
+                                    policy: policy.clone(),
+                                },
+                            }))
+                        }
+                    }
+                }
+            }
+
             // Notify servers of armageddon.
             if act.repo.read_armageddon() {
                 for (addr, server) in act.servers.iter() {
@@ -126,13 +141,28 @@ impl Repo {
                     result = Ok(ServerUpdate::SessionDropped);
                 }
             }
+            ServerRequest::SendBotChat { player_id, message } => {
+                if let Some(arena_id) = server.arena_id {
+                    if self.bot_send_chat(arena_id, player_id, &message) {
+                        result = Ok(ServerUpdate::BotChatSent);
+                    }
+                }
+            }
+            ServerRequest::SendServerChat { message, players } => {
+                if let Some(arena_id) = server.arena_id {
+                    if self.server_send_chat(arena_id, &message, &players) {
+                        result = Ok(ServerUpdate::ServerChatSent);
+                    }
+                }
+            }
             ServerRequest::SetStatus {
                 session_id,
                 location,
                 score,
+                bounty,
             } => {
                 if let Some(arena_id) = server.arena_id {
-                    self.set_status(arena_id, session_id, location, score);
+                    self.set_status(arena_id, session_id, location, score, bounty);
                     result = Ok(ServerUpdate::StatusSet);
                 }
             }
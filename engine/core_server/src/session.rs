@@ -36,6 +36,9 @@ pub struct Play {
     /// The most recent score sent by game server, if any.
     pub score: Option<u32>, // e.g. 1234
 
+    /// The most recent bounty sent by game server, if any.
+    pub bounty: Option<u32>,
+
     /// True if player became captain of a team.
     pub team_captain: bool,
 
@@ -144,6 +147,7 @@ impl Play {
             invited: false,
             renewed: false,
             score: None,
+            bounty: None,
             team_captain: false,
             team_id: None,
         }
@@ -679,7 +683,7 @@ impl Repo {
         arena.date_put = get_unix_time_now();
     }
 
-    // Server sets player's status (location and/or score).
+    // Server sets player's status (location, score, and/or bounty).
     // The liveboard will be updated accordingly.
     pub fn set_status(
         &mut self,
@@ -687,6 +691,7 @@ impl Repo {
         session_id: SessionId,
         location: Option<Location>,
         score: Option<u32>,
+        bounty: Option<u32>,
     ) {
         trace!("set_status(arena={:?}, session={:?})", arena_id, session_id);
         if let Some(arena) = Arena::get_mut(&mut self.arenas, arena_id) {
@@ -702,6 +707,14 @@ impl Repo {
                                 arena.liveboard_changed = true;
                             }
                         }
+                        if let Some(value) = bounty {
+                            if play.bounty != Some(value)
+                                && play.exceeds_score(arena.liveboard_min_score)
+                            {
+                                arena.liveboard_changed = true;
+                            }
+                            play.bounty = Some(value);
+                        }
                     }
                 }
             }
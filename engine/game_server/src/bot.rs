@@ -1,31 +1,48 @@
 // SPDX-FileCopyrightText: 2021 Softbear, Inc.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
-use crate::context::{BotData, PlayerData, PlayerTuple};
+use crate::context::{BotData, ClientAddr, ClientData, PlayerData, PlayerTuple};
 use crate::game_service::{Bot, GameArenaService};
 use common_util::ticks::Ticks;
+use core_protocol::dto::BotPolicyDto;
+use core_protocol::get_unix_time_now;
 use core_protocol::id::PlayerId;
 use rayon::iter::{IndexedParallelIterator, IntoParallelRefMutIterator, ParallelIterator};
+use std::collections::HashMap;
 
 /// Manages the storage and updating of bots.
 pub struct BotZoo<G: GameArenaService> {
     bots: Vec<BotData<G>>,
-    min_players: usize,
-    bot_percent: usize,
+    /// Controls the number and levels of bots. May be changed at any time.
+    policy: BotPolicyDto,
+    /// Scores of real players, for matching bot levels to them (if the policy calls for it).
+    player_scores: Vec<u32>,
+    /// Round-robin index into player_scores.
+    next_player_score: usize,
+    /// Whether bots may send chat messages.
+    chat: bool,
+    /// Time until any bot may send another chat message.
+    chat_cooldown: Ticks,
 }
 
 impl<G: GameArenaService> BotZoo<G> {
+    /// Minimum time between chat messages from any bot in the arena.
+    const CHAT_PERIOD: Ticks = Ticks(50);
+
     /// Creates a new bot zoo.
-    pub fn new(min_players: usize, bot_percent: usize) -> Self {
+    pub fn new(policy: BotPolicyDto, chat: bool) -> Self {
         Self {
-            bots: Vec::with_capacity(min_players.max(bot_percent * 5)),
-            min_players,
-            bot_percent,
+            bots: Vec::with_capacity(policy.min_players.max(policy.bot_percent * 5)),
+            policy,
+            player_scores: Vec::new(),
+            next_player_score: 0,
+            chat,
+            chat_cooldown: Ticks::ZERO,
         }
     }
 
-    /// Updates all bots.
-    pub fn update(&mut self, counter: Ticks, service: &mut G) {
+    /// Updates all bots, returning at most one chat message to send on behalf of a bot.
+    pub fn update(&mut self, counter: Ticks, service: &mut G) -> Option<(PlayerId, String)> {
         {
             let service = &service;
 
@@ -40,29 +57,63 @@ impl<G: GameArenaService> BotZoo<G> {
                 });
         }
 
+        self.chat_cooldown = self.chat_cooldown.saturating_sub(Ticks::ONE);
+        let mut chat = None;
+
         for bot_data in &mut self.bots {
+            // Always take the message, so that bots don't accumulate stale ones.
+            if let Some(message) = bot_data.bot.chat() {
+                if self.chat && chat.is_none() && self.chat_cooldown == Ticks::ZERO {
+                    let player_id = bot_data.player_tuple.player.borrow().player_id;
+                    chat = Some((player_id, message));
+                    self.chat_cooldown = Self::CHAT_PERIOD;
+                }
+            }
+
             if let Some(command) = bot_data.action_buffer.take() {
                 service.player_command(command, &bot_data.player_tuple);
             } else {
                 // Recycle.
                 service.player_left(&bot_data.player_tuple);
                 let player_id = bot_data.player_tuple.player.borrow().player_id;
-                *bot_data = Self::bot_data(player_id);
+                *bot_data =
+                    Self::bot_data(player_id, &self.player_scores, &mut self.next_player_score);
                 service.player_joined(&bot_data.player_tuple);
             };
         }
+
+        chat
     }
 
-    /// Spawns/despawns bots based on number of (real) player clients.
-    pub fn update_count(&mut self, clients: usize, service: &mut G) {
-        let count = self.min_players.max((self.bot_percent * clients) / 100);
+    /// Replaces the policy, taking effect gradually.
+    pub fn set_policy(&mut self, policy: BotPolicyDto) {
+        self.policy = policy;
+    }
+
+    /// Spawns/despawns bots based on (real) player clients and the policy.
+    pub fn update_count(
+        &mut self,
+        clients: &HashMap<ClientAddr<G>, ClientData<G>>,
+        service: &mut G,
+    ) {
+        self.player_scores.clear();
+        if self.policy.match_levels {
+            self.player_scores.extend(
+                clients
+                    .values()
+                    .map(|client_data| client_data.player_tuple.player.borrow().score),
+            );
+        }
+
+        let hour = (get_unix_time_now() / (60 * 60 * 1000) % 24) as usize;
+        let count = self.policy.bot_count(clients.len(), hour);
         self.set_count(count, service);
     }
 
     /// Changes number of bots by spawning/despawning.
     fn set_count(&mut self, count: usize, service: &mut G) {
         // Give server 3 seconds (50 ticks) to create all testing bots.
-        let mut governor = 4.max(self.min_players / 50);
+        let mut governor = 4.max(count.max(self.bots.len()) / 50);
 
         while count < self.bots.len() && governor > 0 {
             governor -= 1;
@@ -79,7 +130,7 @@ impl<G: GameArenaService> BotZoo<G> {
 
             if let Some(next_id) = PlayerId::nth_bot(self.bots.len()) {
                 debug_assert!(next_id.is_bot());
-                let bot = Self::bot_data(next_id);
+                let bot = Self::bot_data(next_id, &self.player_scores, &mut self.next_player_score);
                 service.player_joined(&bot.player_tuple);
                 self.bots.push(bot);
             } else {
@@ -88,7 +139,19 @@ impl<G: GameArenaService> BotZoo<G> {
         }
     }
 
-    fn bot_data(player_id: PlayerId) -> BotData<G> {
-        BotData::new(PlayerTuple::new(PlayerData::new(player_id, None)))
+    /// bot_data creates a bot, which aims for the score of the next real player, if any.
+    fn bot_data(
+        player_id: PlayerId,
+        player_scores: &[u32],
+        next_player_score: &mut usize,
+    ) -> BotData<G> {
+        let mut bot_data = BotData::new(PlayerTuple::new(PlayerData::new(player_id, None)));
+        if !player_scores.is_empty() {
+            *next_player_score = (*next_player_score + 1) % player_scores.len();
+            bot_data
+                .bot
+                .aim_for_score(player_scores[*next_player_score]);
+        }
+        bot_data
     }
 }
@@ -112,6 +112,8 @@ pub struct Context<G: GameArenaService> {
 pub struct CoreStatus {
     pub location: Location,
     pub score: u32,
+    /// Extra score for whoever kills the player.
+    pub bounty: u32,
 }
 
 impl Eq for CoreStatus {}
@@ -120,6 +122,7 @@ impl PartialEq for CoreStatus {
         const THRESHOLD: f32 = 100.0;
         self.location.distance_squared(other.location) <= THRESHOLD.powi(2)
             && self.score == other.score
+            && self.bounty == other.bounty
     }
 }
 
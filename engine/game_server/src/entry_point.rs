@@ -16,7 +16,7 @@ use actix_web::http::{Method, Uri};
 use actix_web::{web, App, Error, HttpRequest, HttpResponse, HttpServer};
 use actix_web_actors::ws;
 use common_util::ticks::Ticks;
-use core_protocol::dto::InvitationDto;
+use core_protocol::dto::{BotPolicyDto, InvitationDto};
 use core_protocol::id::*;
 use core_protocol::web_socket::WebSocketFormat;
 use core_server::app::core_services;
@@ -45,6 +45,21 @@ pub struct Options {
     /// Minimum player count (to be achieved by adding bots)
     #[structopt(short = "p", long, default_value = "30")]
     pub min_players: usize,
+    /// Minimum player count by UTC hour (24 comma-separated values), overriding min_players
+    #[structopt(long, use_delimiter = true)]
+    pub hourly_min_players: Vec<usize>,
+    /// Bots, as a percentage of real players
+    #[structopt(long)]
+    pub bot_percent: Option<usize>,
+    /// Percentage of a min_players bot displaced by each real player
+    #[structopt(long, default_value = "0")]
+    pub bot_displacement_percent: usize,
+    /// Maximum bot count
+    #[structopt(long)]
+    pub max_bots: Option<usize>,
+    /// Match bot levels to those of real players
+    #[structopt(long)]
+    pub match_bot_levels: bool,
     /// Log incoming HTTP requests
     #[cfg_attr(debug_assertions, structopt(long, default_value = "warn"))]
     #[cfg_attr(not(debug_assertions), structopt(long, default_value = "error"))]
@@ -68,6 +83,9 @@ pub struct Options {
     /// Log chats
     #[structopt(long)]
     pub chat_log: Option<String>,
+    /// Allow bots to send chat messages
+    #[structopt(long)]
+    pub bot_chat: bool,
     #[structopt(long)]
     pub linode_personal_access_token: Option<String>,
     // Don't write to the database.
@@ -143,6 +161,19 @@ pub fn entry_point<G: GameArenaService>() {
     logger.filter_module("server_util::ssl", options.debug_watchdog);
     logger.init();
 
+    let mut bot_policy = BotPolicyDto::new(options.min_players);
+    if !options.hourly_min_players.is_empty() {
+        bot_policy.hourly_min_players = Some(options.hourly_min_players.clone());
+    }
+    if let Some(bot_percent) = options.bot_percent {
+        bot_policy.bot_percent = bot_percent;
+    }
+    bot_policy.displacement_percent = options.bot_displacement_percent;
+    if let Some(max_bots) = options.max_bots {
+        bot_policy.max_bots = max_bots;
+    }
+    bot_policy.match_levels = options.match_bot_levels;
+
     let _ = actix_web::rt::System::new().block_on(async move {
         let cloud = options
             .linode_personal_access_token
@@ -159,7 +190,8 @@ pub fn entry_point<G: GameArenaService>() {
         let srv = Infrastructure::start(Infrastructure::new(
             G::new(options.min_players),
             ServerId::new(options.server_id),
-            options.min_players,
+            bot_policy,
+            options.bot_chat,
             core.to_owned(),
         ));
         let domain = Arc::new(options.domain.clone());
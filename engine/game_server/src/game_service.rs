@@ -72,9 +72,24 @@ pub trait GameArenaService: 'static + Unpin + Sized + Sync {
     fn update(&mut self, ticks: Ticks, counter: Ticks);
     /// After sending.
     fn post_update(&mut self) {}
+    /// Called after each update. Returns messages to announce to the arena via chat, along with
+    /// the players whose aliases replace each "{n}" in the corresponding message.
+    fn take_announcements(&mut self) -> Vec<(String, Vec<PlayerId>)> {
+        Vec::new()
+    }
 }
 
 pub trait Bot<G: GameArenaService>: Default + Unpin + Sized + Send {
     /// None indicates quitting.
     fn update<'a>(&mut self, update: G::BotUpdate<'a>, player_id: PlayerId) -> Option<G::Command>;
+
+    /// Called after each update. Returns a chat message the bot would like to send, if any. It
+    /// may be dropped due to rate limiting.
+    fn chat(&mut self) -> Option<String> {
+        None
+    }
+
+    /// Called on new bots, with the score of a real player, if the bot policy calls for bot levels
+    /// to match those of real players.
+    fn aim_for_score(&mut self, _score: u32) {}
 }
@@ -13,7 +13,7 @@ use actix::{
     ResponseActFuture, WrapFuture,
 };
 use common_util::ticks::Ticks;
-use core_protocol::dto::InvitationDto;
+use core_protocol::dto::{BotPolicyDto, InvitationDto};
 use core_protocol::id::{PlayerId, RegionId, ServerId, SessionId};
 use core_protocol::rpc::{ServerRequest, ServerUpdate};
 use core_server::core::Core;
@@ -91,7 +91,8 @@ impl<G: GameArenaService> Infrastructure<G> {
     pub fn new(
         service: G,
         server_id: Option<ServerId>,
-        min_players: usize,
+        bot_policy: BotPolicyDto,
+        bot_chat: bool,
         core: Addr<Core>,
     ) -> Self {
         Self {
@@ -101,7 +102,7 @@ impl<G: GameArenaService> Infrastructure<G> {
                 arena_id: None,
                 counter: Ticks::ZERO,
                 clients: HashMap::new(),
-                bots: BotZoo::new(min_players, if min_players == 0 { 0 } else { 80 }),
+                bots: BotZoo::new(bot_policy, bot_chat),
             },
             ups_monitor: UpsMonitor::new(),
             service,
@@ -113,7 +114,7 @@ impl<G: GameArenaService> Infrastructure<G> {
 
         self.context
             .bots
-            .update_count(self.context.clients.len(), &mut self.service);
+            .update_count(&self.context.clients, &mut self.service);
 
         self.service.update(Ticks::ONE, self.context.counter);
 
@@ -152,7 +153,19 @@ impl<G: GameArenaService> Infrastructure<G> {
             );
         }
 
-        self.context.bots.update(counter, &mut self.service);
+        if let Some((player_id, message)) = self.context.bots.update(counter, &mut self.service) {
+            self.core.do_send(ObserverMessage::Request {
+                observer: ctx.address().recipient(),
+                request: ServerRequest::SendBotChat { player_id, message },
+            });
+        }
+
+        for (message, players) in self.service.take_announcements() {
+            self.core.do_send(ObserverMessage::Request {
+                observer: ctx.address().recipient(),
+                request: ServerRequest::SendServerChat { message, players },
+            });
+        }
 
         self.service.post_update();
 
@@ -186,6 +199,7 @@ impl<G: GameArenaService> Infrastructure<G> {
                     session_id,
                     location: Some(status.location),
                     score: Some(status.score),
+                    bounty: Some(status.bounty),
                 },
                 None => ServerRequest::StopPlay { session_id },
             },
@@ -363,6 +377,12 @@ impl<G: GameArenaService> Handler<ObserverUpdate<ServerUpdate>> for Infrastructu
                     self.context.arena_id = Some(arena_id);
                 }
                 ServerUpdate::ArmageddonStarted { .. } => {}
+                ServerUpdate::BotChatSent => {}
+                ServerUpdate::ServerChatSent => {}
+                ServerUpdate::BotPolicyChanged { policy } => {
+                    info!("bot policy changed to {:?}", policy);
+                    self.context.bots.set_policy(policy);
+                }
                 ServerUpdate::ArenaStopped => {}
                 ServerUpdate::PlayStarted { .. } => {}
                 ServerUpdate::PlayStopped => {}
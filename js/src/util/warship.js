@@ -49,12 +49,19 @@ export function canUpgrade(type, score) {
 	return progressOfUpgrade(type, score) === 1 && hasUpgrades(type);
 }
 
+// Returns the armaments of a boat's loadout (0 being the standard armaments).
+export function loadoutArmaments(type, loadout = 0) {
+	const data = entityData[type];
+	const alternative = loadout > 0 && data.loadouts && data.loadouts[loadout - 1];
+	return alternative ? alternative.armaments : data.armaments;
+}
+
 export function getArmamentType(armamentData) {
 	const aED = entityData[armamentData.type];
 	return `${aED.kind}/${aED.subkind}`;
 }
 
-export function groupArmaments(armaments, consumptions) {
+export function groupArmaments(armaments, consumptions, subsystems) {
 	const groups = {};
 	for (let i = 0; i < armaments.length; i++) {
 		const armament = armaments[i];
@@ -63,13 +70,15 @@ export function groupArmaments(armaments, consumptions) {
 
 		let group = groups[type];
 		if (!group) {
-			group = {type: armament.type, ready: 0, deployed: 0, total: 0, reload: 0};
+			group = {type: armament.type, ready: 0, deployed: 0, damaged: 0, total: 0, reload: 0};
 			groups[type] = group;
 		}
 		group.total++;
 
 		let consumption = armamentConsumption(consumptions, i);
-		if (consumption >= 6553) {
+		if (subsystems && armamentConsumption(subsystems.armaments, i) > 0) {
+			group.damaged++;
+		} else if (consumption >= 6553) {
 			group.deployed++;
 		} else if (consumption > 0) {
 			group.reload += consumption;
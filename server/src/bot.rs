@@ -1,6 +1,7 @@
 // SPDX-FileCopyrightText: 2021 Softbear, Inc.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use crate::bot_memory::{Goal, Memory};
 use crate::server::Server;
 use common::altitude::Altitude;
 use common::angle::Angle;
@@ -12,16 +13,104 @@ use common::protocol::*;
 use common::terrain;
 use common::terrain::Terrain;
 use common::ticks::Ticks;
-use common::util::gen_radius;
+use common::util::{gen_radius, kill_score, score_to_level};
 use core_protocol::id::PlayerId;
 use game_server::game_service::GameArenaService;
 use glam::Vec2;
+use lazy_static::lazy_static;
 use rand::rngs::ThreadRng;
-use rand::seq::IteratorRandom;
+use rand::seq::{IteratorRandom, SliceRandom};
 use rand::{thread_rng, Rng};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+lazy_static! {
+    /// Parameter sets that won the most recent bot tournament (see `bot_tournament`).
+    static ref BOT_PARAMETERS: Vec<BotParameters> =
+        serde_json::from_str(include_str!("./bot_parameters.json"))
+            .expect("could not parse bot parameters json");
+    static ref BOT_CHAT: ChatTemplates = serde_json::from_str(include_str!("./bot_chat.json"))
+        .expect("could not parse bot chat json");
+}
+
+/// ChatTemplates are lines that bots may say in different situations.
+#[derive(Deserialize)]
+struct ChatTemplates {
+    /// Upon joining the game.
+    greeting: Vec<String>,
+    /// After sinking an enemy.
+    taunt: Vec<String>,
+    /// When badly damaged.
+    help: Vec<String>,
+}
+
+/// BotParameters are the tunable constants that make up a bot's personality.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct BotParameters {
+    /// Controls how chill the bots are. If too high, bots are trigger-happy maniacs, and the
+    /// waters get filled with stray torpedoes.
+    pub max_aggression: f32,
+    /// Maximum distance to offset aiming by.
+    pub aim_bias_radius: f32,
+    /// Maximum fraction of a full turn to offset steering by.
+    pub steer_bias: f32,
+    /// Weight of attraction to collectibles.
+    pub collectible_attraction: f32,
+    /// Weight of repulsion from enemies and their weapons.
+    pub enemy_repulsion: f32,
+    /// Weight of repulsion from obstacles.
+    pub obstacle_repulsion: f32,
+    /// Weight of repulsion from land and the world border.
+    pub terrain_repulsion: f32,
+    /// Weight of the spring that keeps teammates together.
+    pub friendly_spring: f32,
+    /// Weight of attraction towards the current goal (see `bot_memory`).
+    pub goal_attraction: f32,
+}
+
+impl Default for BotParameters {
+    fn default() -> Self {
+        Self {
+            max_aggression: 0.1,
+            aim_bias_radius: 10.0,
+            steer_bias: 0.1,
+            collectible_attraction: 1.0,
+            enemy_repulsion: 1.0,
+            obstacle_repulsion: 1.0,
+            terrain_repulsion: 1.0,
+            friendly_spring: 1.0,
+            goal_attraction: 1.0,
+        }
+    }
+}
+
+impl BotParameters {
+    /// weights_mut returns mutable references to all parameters, in a fixed order.
+    pub fn weights_mut(&mut self) -> [&mut f32; 9] {
+        [
+            &mut self.max_aggression,
+            &mut self.aim_bias_radius,
+            &mut self.steer_bias,
+            &mut self.collectible_attraction,
+            &mut self.enemy_repulsion,
+            &mut self.obstacle_repulsion,
+            &mut self.terrain_repulsion,
+            &mut self.friendly_spring,
+            &mut self.goal_attraction,
+        ]
+    }
+
+    /// loaded returns the parameter sets the server was built with.
+    pub fn loaded() -> &'static [BotParameters] {
+        &BOT_PARAMETERS
+    }
+}
 
 /// Bot implements a ship-controlling AI that is, in many ways, equivalent to a player.
 pub struct Bot {
+    /// Personality constants, shared by all bots with the same parameter set.
+    parameters: BotParameters,
     /// Chance of attacking, randomized to improve variety of bots.
     aggression: f32,
     /// Amount to offset steering by. This creates more interesting behavior.
@@ -32,10 +121,35 @@ pub struct Bot {
     level_ambition: u8,
     /// Whether the bot spawned at least once, and therefore is capable of rage-quitting.
     spawned_at_least_once: bool,
+    /// Chance of saying something, when there is something to say.
+    chattiness: f32,
+    /// Message waiting to be sent.
+    chat: Option<String>,
+    /// When the bot last said something, for rate limiting.
+    last_chat: Option<Instant>,
+    /// Score as of the last update, to detect kills.
+    last_score: u32,
+    /// Closest enemy boat as of the last update, to detect kills.
+    last_target: Option<EntityId>,
+    /// Whether the bot asked for help since it last spawned.
+    asked_for_help: bool,
+    /// Where things were, and what the bot is trying to do about them.
+    memory: Memory,
 }
 
 impl Default for Bot {
     fn default() -> Self {
+        let parameters = BotParameters::loaded()
+            .choose(&mut thread_rng())
+            .cloned()
+            .unwrap_or_default();
+        Self::new(parameters)
+    }
+}
+
+impl Bot {
+    /// new returns a bot with the given personality, randomized within its bounds.
+    pub fn new(parameters: BotParameters) -> Self {
         let mut rng = thread_rng();
 
         fn random_level(rng: &mut ThreadRng) -> u8 {
@@ -44,20 +158,43 @@ impl Default for Bot {
 
         Self {
             // Raise aggression to a power such that lower values are more common.
-            aggression: rng.gen::<f32>().powi(2) * Self::MAX_AGGRESSION,
-            steer_bias: rng.gen::<Angle>() * 0.1,
-            aim_bias: gen_radius(&mut rng, 10.0),
+            aggression: rng.gen::<f32>().powi(2) * parameters.max_aggression.clamp(0.0, 1.0),
+            steer_bias: rng.gen::<Angle>() * parameters.steer_bias,
+            aim_bias: gen_radius(&mut rng, parameters.aim_bias_radius),
             // Bias towards lower levels.
             level_ambition: random_level(&mut rng).min(random_level(&mut rng)),
             spawned_at_least_once: false,
+            // Most bots rarely talk.
+            chattiness: rng.gen::<f32>().powi(3),
+            chat: None,
+            last_chat: None,
+            last_score: 0,
+            last_target: None,
+            asked_for_help: false,
+            memory: Memory::default(),
+            parameters,
         }
     }
-}
 
-impl Bot {
-    /// This arbitrary value controls how chill the bots are. If too high, bots are trigger-happy
-    /// maniacs, and the waters get filled with stray torpedoes.
-    const MAX_AGGRESSION: f32 = 0.1;
+    /// Minimum time between chat messages from the same bot.
+    const CHAT_PERIOD: Duration = Duration::from_secs(30);
+
+    /// say picks a line to say from the templates, subject to chattiness and rate limiting.
+    fn say(&mut self, templates: &[String]) {
+        let mut rng = thread_rng();
+        if self
+            .last_chat
+            .map(|last| last.elapsed() < Self::CHAT_PERIOD)
+            .unwrap_or(false)
+            || !rng.gen_bool(self.chattiness as f64)
+        {
+            return;
+        }
+        if let Some(line) = templates.choose(&mut rng) {
+            self.chat = Some(line.to_owned());
+            self.last_chat = Some(Instant::now());
+        }
+    }
 
     /// Returns true if there is land or border at the given position.
     fn is_land_or_border(pos: Vec2, terrain: &Terrain, world_radius: f32) -> bool {
@@ -69,7 +206,7 @@ impl Bot {
     }
 
     /// update processes a complete update and returns some command (or None to quit).
-    fn update<'a, U: 'a + CompleteTrait<'a>>(
+    pub fn update<'a, U: 'a + CompleteTrait<'a>>(
         &mut self,
         mut update: U,
         player_id: PlayerId,
@@ -83,12 +220,24 @@ impl Bot {
             .next()
             .filter(|c| c.is_boat() && c.player_id() == Some(player_id))
         {
+            let first_spawn = !self.spawned_at_least_once;
             self.spawned_at_least_once = true;
 
             let boat_type: EntityType = boat.entity_type().unwrap();
             let data: &EntityData = boat_type.data();
             let health_percent = 1.0 - boat.damage().to_secs() / data.max_health().to_secs();
 
+            let parameters = &self.parameters;
+            let position = boat.transform().position;
+            self.memory.tick();
+
+            // Hunting is reserved for the more aggressive bots.
+            let hunter = self.aggression >= 0.5 * parameters.max_aggression;
+            let hunted = match self.memory.goal() {
+                Some(Goal::Hunt(id, _)) => Some(id),
+                _ => None,
+            };
+
             // Weighted sums of direction vectors for various purposes.
             let mut movement = Vec2::ZERO;
 
@@ -100,10 +249,13 @@ impl Bot {
                 attract(weighted_sum, -target_delta, distance_squared);
             };
 
-            let spring = |weighted_sum: &mut Vec2, target_delta: Vec2, desired_distance: f32| {
+            let spring = |weighted_sum: &mut Vec2,
+                          target_delta: Vec2,
+                          desired_distance: f32,
+                          weight: f32| {
                 let distance = target_delta.length();
                 let displacement = distance - desired_distance;
-                *weighted_sum = target_delta * displacement / (displacement.powi(2) + 1.0);
+                *weighted_sum = target_delta * weight * displacement / (displacement.powi(2) + 1.0);
             };
 
             // Terrain.
@@ -113,15 +265,21 @@ impl Bot {
                     Angle::from_radians(i as f32 * (2.0 * std::f32::consts::PI / SAMPLES as f32));
                 let delta_position = angle.to_vec() * data.length;
                 if Self::is_land_or_border(
-                    boat.transform().position + delta_position,
+                    position + delta_position,
                     terrain,
                     update.world_radius(),
                 ) {
-                    repel(&mut movement, delta_position, 0.5 * data.length.powi(2));
+                    self.memory.see_obstruction(position + delta_position);
+                    repel(
+                        &mut movement,
+                        delta_position * parameters.terrain_repulsion,
+                        0.5 * data.length.powi(2),
+                    );
                 }
             }
 
             let mut closest_enemy: Option<(U::Contact, f32)> = None;
+            let mut last_target_seen = false;
 
             // Scan sensor contacts to help make decisions.
             for contact in contacts {
@@ -130,20 +288,46 @@ impl Bot {
                     continue;
                 }
 
+                if Some(contact.id()) == self.last_target {
+                    last_target_seen = true;
+                }
+
                 if let Some(contact_data) = contact.entity_type().map(EntityType::data) {
-                    let delta_position = contact.transform().position - boat.transform().position;
+                    let delta_position = contact.transform().position - position;
                     let distance_squared = delta_position.length_squared();
 
                     let friendly = contact.player_id() == Some(player_id);
 
                     if contact_data.kind == EntityKind::Collectible {
-                        attract(&mut movement, delta_position, distance_squared);
+                        self.memory.see_collectible(contact.transform().position);
+                        attract(
+                            &mut movement,
+                            delta_position * parameters.collectible_attraction,
+                            distance_squared,
+                        );
+                    } else if contact_data.sub_kind == EntitySubKind::Wreck {
+                        // Wrecks are only of interest to those that can salvage them.
+                        if matches!(
+                            data.sub_kind,
+                            EntitySubKind::Dredger | EntitySubKind::Submarine
+                        ) {
+                            attract(
+                                &mut movement,
+                                delta_position * parameters.collectible_attraction,
+                                distance_squared,
+                            );
+                        }
                     } else if (!friendly || contact_data.kind == EntityKind::Boat)
                         && !(!friendly
                             && contact_data.kind == EntityKind::Boat
                             && data.sub_kind == EntitySubKind::Ram)
+                        && Some(contact.id()) != hunted
                     {
-                        repel(&mut movement, delta_position, distance_squared);
+                        repel(
+                            &mut movement,
+                            delta_position * parameters.enemy_repulsion,
+                            distance_squared,
+                        );
                     }
 
                     if friendly {
@@ -152,19 +336,23 @@ impl Bot {
                                 &mut movement,
                                 delta_position,
                                 data.radius + contact_data.radius,
+                                parameters.friendly_spring,
                             );
                         }
                     } else if match contact_data.kind {
                         // Don't kill smol/peaceful boats unless they get too close.
                         EntityKind::Boat => {
-                            (contact_data.level + 1 >= data.level
+                            let prey = (contact_data.level + 1 >= data.level
                                 && !matches!(
                                     contact_data.sub_kind,
                                     EntitySubKind::Dredger | EntitySubKind::Icebreaker
                                 ))
                                 || contact.player_id().map(|id| id.is_bot()).unwrap_or(false)
                                 || distance_squared < 1.5 * data.radius.powi(2)
-                                || health_percent < 1.0 / 3.0
+                                || health_percent < 1.0 / 3.0;
+                            self.memory
+                                .see_enemy(contact.id(), contact.transform().position, prey);
+                            prey
                         }
                         EntityKind::Aircraft => true,
                         EntityKind::Weapon => matches!(
@@ -172,11 +360,13 @@ impl Bot {
                             EntitySubKind::Missile | EntitySubKind::Torpedo
                         ),
                         EntityKind::Obstacle => {
-                            repel(
-                                &mut movement,
-                                delta_position,
-                                (distance_squared - contact_data.radius.powi(2)).max(0.0),
-                            );
+                            if contact_data.sub_kind != EntitySubKind::Wreck {
+                                repel(
+                                    &mut movement,
+                                    delta_position * parameters.obstacle_repulsion,
+                                    (distance_squared - contact_data.radius.powi(2)).max(0.0),
+                                );
+                            }
                             false
                         }
                         _ => false,
@@ -192,17 +382,43 @@ impl Bot {
                 }
             }
 
+            // Pursue goal, unless something more pressing is nearby.
+            self.memory
+                .forget_unseen(position, data.sensors.visual.range * 0.5);
+            let goal_direction = self.memory.decide(
+                position,
+                health_percent,
+                hunter,
+                update.world_radius(),
+                &mut rng,
+            );
+            movement +=
+                goal_direction.normalize_or_zero() * parameters.goal_attraction / data.length;
+
+            let closest_enemy_boat = closest_enemy
+                .as_ref()
+                .filter(|(enemy, _)| enemy.is_boat())
+                .map(|(enemy, _)| enemy.id());
+
             let mut best_firing_solution = None;
 
             if let Some((enemy, _)) = closest_enemy {
                 let reloads = boat.reloads();
                 let enemy_data = enemy.data();
-                for (i, armament) in data.armaments.iter().enumerate() {
+                for (i, armament) in boat.armaments().iter().enumerate() {
                     if reloads[i] > Ticks::ZERO {
                         // Not yet reloaded.
                         continue;
                     }
 
+                    if boat
+                        .subsystems()
+                        .map_or(false, |s| s.is_armament_disabled(i))
+                    {
+                        // Damaged.
+                        continue;
+                    }
+
                     let armament_entity_data: &EntityData = armament.entity_type.data();
                     if !matches!(
                         armament_entity_data.kind,
@@ -215,6 +431,9 @@ impl Bot {
                         EntityKind::Aircraft | EntityKind::Weapon => {
                             if enemy.altitude().is_airborne() {
                                 matches!(armament_entity_data.sub_kind, EntitySubKind::Sam)
+                                    || (enemy_data.sub_kind == EntitySubKind::Missile
+                                        && armament_entity_data.kind == EntityKind::Decoy
+                                        && armament_entity_data.sub_kind == EntitySubKind::Radar)
                             } else if enemy_data.sub_kind == EntitySubKind::Torpedo
                                 && enemy_data.sensors.sonar.range > 0.0
                             {
@@ -268,7 +487,8 @@ impl Bot {
                         }
                     }
 
-                    let transform = *boat.transform() + data.armament_transform(boat.turrets(), i);
+                    let transform = *boat.transform()
+                        + data.armament_transform(boat.loadout(), boat.turrets(), i);
                     let angle = Angle::from(enemy.transform().position - transform.position);
 
                     let mut angle_diff = (angle - transform.direction).abs();
@@ -300,12 +520,17 @@ impl Bot {
             let mut ret = Command::Control(Control {
                 guidance: Some(Guidance {
                     direction_target: Angle::from(movement) + self.steer_bias,
-                    velocity_target: data.speed * 0.8,
+                    velocity_target: data.speed
+                        * if self.memory.goal() == Some(Goal::Retreat) {
+                            1.0
+                        } else {
+                            0.8
+                        },
                 }),
                 altitude_target: if data.sub_kind == EntitySubKind::Submarine {
                     // More positive values mean want to surface, more negative values mean want to dive.
-                    let surface_bias =
-                        health_percent - self.aggression * (1.0 / Self::MAX_AGGRESSION);
+                    let surface_bias = health_percent
+                        - self.aggression / parameters.max_aggression.max(f32::EPSILON);
 
                     // Hysteresis.
                     if boat.altitude().is_submerged() && surface_bias >= 0.1 {
@@ -335,22 +560,52 @@ impl Bot {
                     .upgrade_options(update.score(), true)
                     .choose(&mut rng)
                 {
-                    ret = Command::Upgrade(Upgrade { entity_type });
+                    ret = Command::Upgrade(Upgrade {
+                        entity_type,
+                        loadout: Self::choose_loadout(entity_type, &mut rng),
+                    });
                 }
             }
 
+            // Chat.
+            let score = update.score();
+            if first_spawn {
+                self.say(&BOT_CHAT.greeting);
+            } else if self.last_target.is_some()
+                && !last_target_seen
+                && score >= self.last_score + kill_score(0)
+            {
+                // Target vanished at the same time as score went up, probably sunk it.
+                self.say(&BOT_CHAT.taunt);
+            } else if health_percent < 0.25 && !self.asked_for_help {
+                self.asked_for_help = true;
+                self.say(&BOT_CHAT.help);
+            }
+            self.last_score = score;
+            self.last_target = closest_enemy_boat;
+
             Some(ret)
         } else if self.spawned_at_least_once && rng.gen_bool(1.0 / 3.0) {
             // Rage quit.
             None
         } else {
+            self.asked_for_help = false;
+            self.last_target = None;
+            self.memory = Memory::default();
+            let entity_type = EntityType::spawn_options(true)
+                .choose(&mut rng)
+                .expect("there must be at least one entity type to spawn as");
             Some(Command::Spawn(Spawn {
-                entity_type: EntityType::spawn_options(true)
-                    .choose(&mut rng)
-                    .expect("there must be at least one entity type to spawn as"),
+                entity_type,
+                loadout: Self::choose_loadout(entity_type, &mut rng),
             }))
         }
     }
+
+    /// Chooses a random loadout for a boat type.
+    fn choose_loadout(entity_type: EntityType, rng: &mut ThreadRng) -> u8 {
+        rng.gen_range(0..=entity_type.data().loadouts.len() as u8)
+    }
 }
 
 impl game_server::game_service::Bot<Server> for Bot {
@@ -361,4 +616,26 @@ impl game_server::game_service::Bot<Server> for Bot {
     ) -> Option<<Server as GameArenaService>::Command> {
         self.update(update, player_id)
     }
+
+    fn chat(&mut self) -> Option<String> {
+        self.chat.take()
+    }
+
+    fn aim_for_score(&mut self, score: u32) {
+        self.level_ambition = score_to_level(score);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bot::BotParameters;
+
+    #[test]
+    fn bot_parameters() {
+        let loaded = BotParameters::loaded();
+        assert!(!loaded.is_empty());
+        for parameters in loaded {
+            assert!((0.0..=1.0).contains(&parameters.max_aggression));
+        }
+    }
 }
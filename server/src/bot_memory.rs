@@ -0,0 +1,346 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use common::entity::EntityId;
+use common::util::gen_radius;
+use glam::Vec2;
+use rand::Rng;
+
+/// Goal is what a bot is trying to achieve across updates, beyond reacting to its surroundings.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Goal {
+    /// Explore by heading towards a waypoint.
+    Patrol(Vec2),
+    /// Head towards a remembered cluster of collectibles.
+    Collect(Vec2),
+    /// Chase an enemy boat to where it was last seen.
+    Hunt(EntityId, Vec2),
+    /// Get away from remembered threats, to repair in peace.
+    Retreat,
+}
+
+/// An enemy boat, as of when it was last seen.
+struct EnemySighting {
+    id: EntityId,
+    position: Vec2,
+    /// Whether it is worth chasing.
+    prey: bool,
+    seen: u32,
+}
+
+/// A group of collectibles that were seen close together.
+struct Cluster {
+    position: Vec2,
+    /// Number of sightings merged into the position, up to a limit.
+    weight: u32,
+    seen: u32,
+}
+
+/// A position where land or the world border was found.
+struct Obstruction {
+    position: Vec2,
+    seen: u32,
+}
+
+/// Memory is a bot's short-term knowledge of its surroundings, and its current goal.
+#[derive(Default)]
+pub struct Memory {
+    /// Number of updates since the bot spawned, used for timestamps.
+    now: u32,
+    enemies: Vec<EnemySighting>,
+    collectibles: Vec<Cluster>,
+    obstructions: Vec<Obstruction>,
+    goal: Option<Goal>,
+    /// Closest the bot has gotten to the target of its goal.
+    best_distance: f32,
+    /// When best_distance last improved, to detect being stuck.
+    last_progress: u32,
+}
+
+impl Memory {
+    /// How long to remember enemies (in updates).
+    const ENEMY_MEMORY: u32 = 150;
+    /// How long to remember collectibles (in updates).
+    const COLLECTIBLE_MEMORY: u32 = 300;
+    /// How long to remember land and the world border (in updates).
+    const OBSTRUCTION_MEMORY: u32 = 600;
+    /// How long a goal may go without progress before it is abandoned (in updates).
+    const PATIENCE: u32 = 100;
+    const MAX_ENEMIES: usize = 8;
+    const MAX_COLLECTIBLES: usize = 8;
+    const MAX_OBSTRUCTIONS: usize = 32;
+    /// Collectibles within this distance of a cluster are merged into it.
+    const CLUSTER_RADIUS: f32 = 150.0;
+    /// Obstructions within this distance of an existing one are redundant.
+    const OBSTRUCTION_RADIUS: f32 = 30.0;
+    /// Health below which bots retreat, if they know of threats.
+    const RETREAT_HEALTH: f32 = 0.4;
+
+    /// tick advances time, forgetting anything that is too old to be relevant.
+    pub fn tick(&mut self) {
+        self.now += 1;
+        let now = self.now;
+        self.enemies.retain(|e| now - e.seen <= Self::ENEMY_MEMORY);
+        self.collectibles
+            .retain(|c| now - c.seen <= Self::COLLECTIBLE_MEMORY);
+        self.obstructions
+            .retain(|o| now - o.seen <= Self::OBSTRUCTION_MEMORY);
+    }
+
+    /// see_enemy remembers an enemy boat's position.
+    pub fn see_enemy(&mut self, id: EntityId, position: Vec2, prey: bool) {
+        let now = self.now;
+        if let Some(existing) = self.enemies.iter_mut().find(|e| e.id == id) {
+            existing.position = position;
+            existing.prey = prey;
+            existing.seen = now;
+            return;
+        }
+        if self.enemies.len() >= Self::MAX_ENEMIES {
+            Self::forget_oldest(&mut self.enemies, |e| e.seen);
+        }
+        self.enemies.push(EnemySighting {
+            id,
+            position,
+            prey,
+            seen: now,
+        });
+    }
+
+    /// see_collectible merges a collectible into the nearest cluster, or starts a new one.
+    pub fn see_collectible(&mut self, position: Vec2) {
+        const MAX_WEIGHT: u32 = 10;
+
+        let now = self.now;
+        if let Some(cluster) = self
+            .collectibles
+            .iter_mut()
+            .find(|c| c.position.distance_squared(position) < Self::CLUSTER_RADIUS.powi(2))
+        {
+            cluster.weight = (cluster.weight + 1).min(MAX_WEIGHT);
+            cluster.position += (position - cluster.position) / cluster.weight as f32;
+            cluster.seen = now;
+            return;
+        }
+        if self.collectibles.len() >= Self::MAX_COLLECTIBLES {
+            Self::forget_oldest(&mut self.collectibles, |c| c.seen);
+        }
+        self.collectibles.push(Cluster {
+            position,
+            weight: 1,
+            seen: now,
+        });
+    }
+
+    /// see_obstruction remembers that there is land or the world border at a position.
+    pub fn see_obstruction(&mut self, position: Vec2) {
+        let now = self.now;
+        if let Some(existing) = self
+            .obstructions
+            .iter_mut()
+            .find(|o| o.position.distance_squared(position) < Self::OBSTRUCTION_RADIUS.powi(2))
+        {
+            existing.seen = now;
+            return;
+        }
+        if self.obstructions.len() >= Self::MAX_OBSTRUCTIONS {
+            Self::forget_oldest(&mut self.obstructions, |o| o.seen);
+        }
+        self.obstructions.push(Obstruction {
+            position,
+            seen: now,
+        });
+    }
+
+    /// forget_unseen forgets clusters and enemies that should have been seen from the position
+    /// during this update, but weren't, because they are gone.
+    pub fn forget_unseen(&mut self, position: Vec2, radius: f32) {
+        let now = self.now;
+        let visible = |p: Vec2| p.distance_squared(position) < radius.powi(2);
+        self.collectibles
+            .retain(|c| c.seen == now || !visible(c.position));
+        self.enemies
+            .retain(|e| e.seen == now || !visible(e.position));
+    }
+
+    /// goal returns the goal as of the last decision.
+    pub fn goal(&self) -> Option<Goal> {
+        self.goal
+    }
+
+    /// decide picks a goal, based on what is remembered, and returns the direction to pursue it
+    /// in, which is not normalized.
+    pub fn decide(
+        &mut self,
+        position: Vec2,
+        health_percent: f32,
+        hunter: bool,
+        world_radius: f32,
+        rng: &mut impl Rng,
+    ) -> Vec2 {
+        let closest = |a: &Vec2, b: &Vec2| {
+            a.distance_squared(position)
+                .partial_cmp(&b.distance_squared(position))
+                .unwrap()
+        };
+
+        let goal = if health_percent < Self::RETREAT_HEALTH && !self.enemies.is_empty() {
+            Goal::Retreat
+        } else if let Some(prey) = self
+            .enemies
+            .iter()
+            .filter(|e| hunter && e.prey)
+            .min_by(|a, b| closest(&a.position, &b.position))
+        {
+            Goal::Hunt(prey.id, prey.position)
+        } else if let Some(cluster) = self.collectibles.iter().map(|c| c.position).min_by(closest) {
+            Goal::Collect(cluster)
+        } else {
+            match self.goal {
+                Some(Goal::Patrol(waypoint)) => Goal::Patrol(waypoint),
+                _ => Goal::Patrol(self.waypoint(position, world_radius, rng)),
+            }
+        };
+
+        // Only measure progress towards the same target.
+        let target = Self::target(goal);
+        if target != self.goal.and_then(Self::target) {
+            self.best_distance = f32::INFINITY;
+            self.last_progress = self.now;
+        }
+        self.goal = Some(goal);
+
+        if let Some(target) = target {
+            let distance = target.distance(position);
+            if distance < self.best_distance - 1.0 {
+                self.best_distance = distance;
+                self.last_progress = self.now;
+            } else if self.now - self.last_progress > Self::PATIENCE {
+                // Stuck, so give up on the target (it will be reconsidered next update).
+                self.abandon(target);
+                self.goal = None;
+                return Vec2::ZERO;
+            }
+
+            if matches!(goal, Goal::Patrol(_)) && distance < Self::CLUSTER_RADIUS {
+                // Reached waypoint, so pick the next one.
+                self.goal = Some(Goal::Patrol(self.waypoint(position, world_radius, rng)));
+            }
+
+            target - position
+        } else {
+            // Retreat away from threats, weighting recent and close ones more.
+            let now = self.now;
+            self.enemies
+                .iter()
+                .map(|e| {
+                    let delta = position - e.position;
+                    let recency = 1.0 - (now - e.seen) as f32 / Self::ENEMY_MEMORY as f32;
+                    delta.normalize_or_zero() * recency / (1.0 + delta.length() * 0.01)
+                })
+                .sum()
+        }
+    }
+
+    /// target returns the position a goal leads to, if any.
+    fn target(goal: Goal) -> Option<Vec2> {
+        match goal {
+            Goal::Patrol(target) | Goal::Collect(target) | Goal::Hunt(_, target) => Some(target),
+            Goal::Retreat => None,
+        }
+    }
+
+    /// abandon forgets whatever is at the target, so it won't be chosen again.
+    fn abandon(&mut self, target: Vec2) {
+        self.collectibles.retain(|c| c.position != target);
+        self.enemies.retain(|e| e.position != target);
+    }
+
+    /// waypoint picks a random position to patrol towards, preferring one that isn't behind known
+    /// obstructions.
+    fn waypoint(&self, position: Vec2, world_radius: f32, rng: &mut impl Rng) -> Vec2 {
+        const ATTEMPTS: usize = 5;
+
+        let mut candidate = Vec2::ZERO;
+        for _ in 0..ATTEMPTS {
+            // Don't wander too far at once, so that new discoveries can change the plan.
+            candidate = position + gen_radius(rng, world_radius.min(1500.0));
+            if candidate.length_squared() > (world_radius * 0.9).powi(2) {
+                candidate = candidate.normalize_or_zero() * world_radius * 0.9;
+            }
+            if !self.is_obstructed(position, candidate) {
+                break;
+            }
+        }
+        candidate
+    }
+
+    /// is_obstructed returns true if a known obstruction is on the straight path between two
+    /// positions.
+    fn is_obstructed(&self, start: Vec2, end: Vec2) -> bool {
+        let segment = end - start;
+        let length_squared = segment.length_squared().max(f32::EPSILON);
+        self.obstructions.iter().any(|o| {
+            let t = ((o.position - start).dot(segment) / length_squared).clamp(0.0, 1.0);
+            let closest = start + segment * t;
+            closest.distance_squared(o.position) < (2.0 * Self::OBSTRUCTION_RADIUS).powi(2)
+        })
+    }
+
+    fn forget_oldest<T>(items: &mut Vec<T>, seen: impl Fn(&T) -> u32) {
+        if let Some(i) = items
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, item)| seen(item))
+            .map(|(i, _)| i)
+        {
+            items.swap_remove(i);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bot_memory::{Goal, Memory};
+    use common::entity::EntityId;
+    use glam::Vec2;
+    use rand::thread_rng;
+
+    #[test]
+    fn goals() {
+        let mut rng = thread_rng();
+        let mut memory = Memory::default();
+        let position = Vec2::ZERO;
+
+        memory.tick();
+        memory.decide(position, 1.0, true, 5000.0, &mut rng);
+        assert!(matches!(memory.goal, Some(Goal::Patrol(_))));
+
+        memory.tick();
+        memory.see_collectible(Vec2::new(500.0, 0.0));
+        memory.see_collectible(Vec2::new(520.0, 0.0));
+        let direction = memory.decide(position, 1.0, true, 5000.0, &mut rng);
+        assert!(matches!(memory.goal, Some(Goal::Collect(_))));
+        assert!(direction.x > 0.0);
+
+        memory.tick();
+        let enemy = EntityId::new(1).unwrap();
+        memory.see_enemy(enemy, Vec2::new(-300.0, 0.0), true);
+        memory.decide(position, 1.0, true, 5000.0, &mut rng);
+        assert_eq!(
+            memory.goal(),
+            Some(Goal::Hunt(enemy, Vec2::new(-300.0, 0.0)))
+        );
+
+        memory.tick();
+        let direction = memory.decide(position, 0.2, true, 5000.0, &mut rng);
+        assert_eq!(memory.goal, Some(Goal::Retreat));
+        assert!(direction.x > 0.0);
+
+        for _ in 0..=Memory::COLLECTIBLE_MEMORY {
+            memory.tick();
+        }
+        memory.decide(position, 1.0, true, 5000.0, &mut rng);
+        assert!(matches!(memory.goal, Some(Goal::Patrol(_))));
+    }
+}
@@ -0,0 +1,274 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+//! Headless tournaments between bot parameter sets, used to evolve bots that are fun to play
+//! against. Run via `cargo run --release -- tune-bots [generations] [output]` whenever entity data
+//! changes, and commit the resulting `bot_parameters.json`.
+
+use crate::bot::{Bot, BotParameters};
+use crate::server::Server;
+use common::ticks::Ticks;
+use core_protocol::id::PlayerId;
+use game_server::context::{PlayerData, PlayerTuple};
+use game_server::game_service::GameArenaService;
+use log::info;
+use rand::seq::SliceRandom;
+use rand::{thread_rng, Rng};
+use std::fs;
+use std::sync::Arc;
+
+/// Options for tune_bots.
+pub struct TournamentOptions {
+    /// How many rounds of selection to run.
+    pub generations: usize,
+    /// How many parameter sets compete in each generation.
+    pub population: usize,
+    /// How many parameter sets survive each generation (and are written out at the end).
+    pub survivors: usize,
+    /// How many bots play with each parameter set.
+    pub bots_per_set: usize,
+    /// How long each tournament lasts.
+    pub duration: Ticks,
+    /// Where to write the winning parameter sets.
+    pub output: String,
+}
+
+impl Default for TournamentOptions {
+    fn default() -> Self {
+        Self {
+            generations: 20,
+            population: 12,
+            survivors: 4,
+            bots_per_set: 4,
+            duration: Ticks::from_secs(300.0),
+            output: String::from("src/bot_parameters.json"),
+        }
+    }
+}
+
+impl TournamentOptions {
+    /// from_args parses `[generations] [output]`, falling back to defaults.
+    pub fn from_args(mut args: impl Iterator<Item = String>) -> Self {
+        let mut options = Self::default();
+        if let Some(generations) = args.next().and_then(|s| s.parse().ok()) {
+            options.generations = generations;
+        }
+        if let Some(output) = args.next() {
+            options.output = output;
+        }
+        options
+    }
+}
+
+/// A bot playing in a tournament on behalf of a parameter set.
+struct Contestant {
+    set: usize,
+    bot: Bot,
+    player_tuple: Arc<PlayerTuple<Server>>,
+}
+
+impl Contestant {
+    fn new(set: usize, parameters: &BotParameters, player_id: PlayerId) -> Self {
+        Self {
+            set,
+            bot: Bot::new(parameters.clone()),
+            player_tuple: Arc::new(PlayerTuple::new(PlayerData::new(player_id, None))),
+        }
+    }
+}
+
+/// tune_bots evolves bot parameters, starting from the ones the server was built with, and writes
+/// the best ones to the output file.
+pub fn tune_bots(options: TournamentOptions) {
+    // The game server's logger is only initialized by its entry point.
+    let _ = env_logger::builder()
+        .format_timestamp(None)
+        .filter_module(module_path!(), log::LevelFilter::Info)
+        .try_init();
+
+    let mut rng = thread_rng();
+    let survivors = options.survivors.clamp(1, options.population.max(1));
+
+    let mut population: Vec<BotParameters> = BotParameters::loaded().to_vec();
+    population.truncate(options.population);
+    if population.is_empty() {
+        population.push(BotParameters::default());
+    }
+
+    for generation in 0..options.generations {
+        // Refill population with offspring of the fittest.
+        let parents = population.len();
+        while population.len() < options.population {
+            let a = &population[rng.gen_range(0..parents)];
+            let b = &population[rng.gen_range(0..parents)];
+            let child = mutate(crossover(a, b));
+            population.push(child);
+        }
+
+        let fitness = run_tournament(&population, &options);
+
+        let mut ranked: Vec<(BotParameters, f32)> = population.into_iter().zip(fitness).collect();
+        ranked.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+        ranked.truncate(survivors);
+
+        info!(
+            "generation {}: best fitness {:.1}, worst survivor {:.1}",
+            generation,
+            ranked.first().map(|(_, f)| *f).unwrap_or(0.0),
+            ranked.last().map(|(_, f)| *f).unwrap_or(0.0)
+        );
+
+        population = ranked.into_iter().map(|(p, _)| p).collect();
+    }
+
+    let json = serde_json::to_string_pretty(&population).expect("could not serialize parameters");
+    fs::write(&options.output, json + "\n").expect("could not write parameters");
+    info!(
+        "wrote {} parameter sets to {}",
+        population.len(),
+        options.output
+    );
+}
+
+/// run_tournament plays a headless arena where each parameter set controls bots_per_set bots, and
+/// returns the fitness of each parameter set.
+///
+/// Fitness is the score of each set's bots, sampled once per second and averaged. This rewards
+/// bots that survive, collect, and fight, rather than ones that sink early or hide forever.
+fn run_tournament(population: &[BotParameters], options: &TournamentOptions) -> Vec<f32> {
+    let total = population.len() * options.bots_per_set;
+    let mut server = Server::new(total);
+
+    let mut contestants: Vec<Contestant> = (0..total)
+        .map(|i| {
+            let set = i % population.len();
+            let player_id = PlayerId::nth_bot(i).expect("should not run out of ids");
+            Contestant::new(set, &population[set], player_id)
+        })
+        .collect();
+
+    for contestant in &contestants {
+        server.player_joined(&contestant.player_tuple);
+    }
+
+    let mut fitness = vec![0f32; population.len()];
+    let mut samples = 0u32;
+    let mut counter = Ticks::ZERO;
+
+    while counter < options.duration {
+        for contestant in &mut contestants {
+            let player_id = contestant.player_tuple.borrow_player().player_id;
+            let update = server.get_bot_update(counter, &contestant.player_tuple);
+            let command = <Bot as game_server::game_service::Bot<Server>>::update(
+                &mut contestant.bot,
+                update,
+                player_id,
+            );
+
+            if let Some(command) = command {
+                server.player_command(command, &contestant.player_tuple);
+            } else {
+                // Recycle, just like the bot zoo.
+                server.player_left(&contestant.player_tuple);
+                *contestant =
+                    Contestant::new(contestant.set, &population[contestant.set], player_id);
+                server.player_joined(&contestant.player_tuple);
+            }
+        }
+
+        server.update(Ticks::ONE, counter);
+        server.post_update();
+        counter = counter.wrapping_add(Ticks::ONE);
+
+        if counter % Ticks::FREQUENCY_HZ == Ticks::ZERO {
+            samples += 1;
+            for contestant in &contestants {
+                fitness[contestant.set] += contestant.player_tuple.borrow_player().score as f32;
+            }
+        }
+    }
+
+    let divisor = (samples.max(1) as usize * options.bots_per_set.max(1)) as f32;
+    fitness.iter_mut().for_each(|f| *f /= divisor);
+    fitness
+}
+
+/// crossover picks each parameter from either parent.
+fn crossover(a: &BotParameters, b: &BotParameters) -> BotParameters {
+    let mut rng = thread_rng();
+    let mut child = a.clone();
+    let mut b = b.clone();
+    for (c, b) in child.weights_mut().iter_mut().zip(b.weights_mut().iter()) {
+        if rng.gen_bool(0.5) {
+            **c = **b;
+        }
+    }
+    child
+}
+
+/// mutate scales a few parameters by a random factor.
+fn mutate(mut parameters: BotParameters) -> BotParameters {
+    let mut rng = thread_rng();
+    let mut weights = parameters.weights_mut();
+    weights.shuffle(&mut rng);
+    for weight in weights.iter_mut().take(3) {
+        **weight = (**weight * rng.gen_range(0.7..1.4)).max(0.0);
+    }
+    parameters.max_aggression = parameters.max_aggression.min(1.0);
+    parameters
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bot::BotParameters;
+    use crate::bot_tournament::{crossover, mutate};
+
+    fn parents() -> (BotParameters, BotParameters) {
+        let mut a = BotParameters::default();
+        let mut b = BotParameters::default();
+        for (i, (a, b)) in a
+            .weights_mut()
+            .iter_mut()
+            .zip(b.weights_mut().iter_mut())
+            .enumerate()
+        {
+            **a = i as f32 * 0.1;
+            **b = i as f32 * 0.1 + 0.05;
+        }
+        (a, b)
+    }
+
+    #[test]
+    fn crossover_takes_from_parents() {
+        let (mut a, mut b) = parents();
+        for _ in 0..100 {
+            let mut child = crossover(&a, &b);
+            for ((c, a), b) in child
+                .weights_mut()
+                .iter_mut()
+                .zip(a.weights_mut().iter_mut())
+                .zip(b.weights_mut().iter_mut())
+            {
+                assert!(**c == **a || **c == **b, "{} not from {} or {}", c, a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn mutate_stays_in_bounds() {
+        let (mut original, _) = parents();
+        original.max_aggression = 0.9;
+        for _ in 0..100 {
+            let mut mutated = mutate(original.clone());
+            assert!(mutated.max_aggression <= 1.0);
+            for (m, o) in mutated
+                .weights_mut()
+                .iter_mut()
+                .zip(original.weights_mut().iter_mut())
+            {
+                assert!(**m >= 0.0);
+                assert!(**m >= **o * 0.7 && **m <= **o * 1.4, "{} from {}", m, o);
+            }
+        }
+    }
+}
@@ -1,8 +1,11 @@
 // SPDX-FileCopyrightText: 2021 Softbear, Inc.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use arrayvec::ArrayVec;
+use common::entity::EntityData;
 use common::transform::Transform;
 use glam::Vec2;
+use std::iter;
 
 /// radius_collision performs a simple radius check. This is faster but less accurate than SAT.
 pub fn radius_collision(
@@ -23,13 +26,14 @@ pub fn radius_collision(
     d2 <= r2
 }
 
-/// sat_collision performs continuous rectangle-based separating axis theorem collision.
+/// sat_collision performs continuous convex polygon-based separating axis theorem collision. Hulls
+/// are counter-clockwise, relative to their transforms.
 pub fn sat_collision(
-    mut transform: Transform,
-    mut dimensions: Vec2,
+    transform: Transform,
+    hull: &[Vec2],
     radius: f32,
-    mut other_transform: Transform,
-    mut other_dimensions: Vec2,
+    other_transform: Transform,
+    other_hull: &[Vec2],
     other_radius: f32,
     delta_seconds: f32,
 ) -> bool {
@@ -44,89 +48,214 @@ pub fn sat_collision(
         return false;
     }
 
-    let axis_normal = transform.direction.to_vec();
-    let other_axis_normal = other_transform.direction.to_vec();
-
-    transform.position += axis_normal * (sweep * 0.5);
-    other_transform.position += other_axis_normal * (other_sweep * 0.5);
-
-    dimensions.x += sweep;
-    other_dimensions.x += other_sweep;
-
-    // Make math easier later on
-    other_dimensions *= 0.5;
-    dimensions *= 0.5;
-
-    sat_collision_half(
-        transform.position,
-        other_transform.position,
-        axis_normal,
-        other_axis_normal,
-        dimensions,
-        other_dimensions,
-    ) && sat_collision_half(
-        other_transform.position,
-        transform.position,
-        other_axis_normal,
-        axis_normal,
-        other_dimensions,
-        dimensions,
-    )
+    // Sweep one polygon relative to the other.
+    let relative_sweep =
+        transform.direction.to_vec() * sweep - other_transform.direction.to_vec() * other_sweep;
+
+    let points: ArrayVec<Vec2, { EntityData::MAX_HULL_VERTICES }> =
+        hull.iter().map(|&v| transform.to_global(v)).collect();
+    let other_points: ArrayVec<Vec2, { EntityData::MAX_HULL_VERTICES }> = other_hull
+        .iter()
+        .map(|&v| other_transform.to_global(v))
+        .collect();
+
+    // Normals of the edges of both polygons, and of the sweep, are the potential separating axes.
+    let axes = edge_normals(&points)
+        .chain(edge_normals(&other_points))
+        .chain(iter::once(relative_sweep.perp()));
+
+    for axis in axes {
+        if axis == Vec2::ZERO {
+            // Degenerate edge or no sweep.
+            continue;
+        }
+
+        let (min, max) = project(&points, axis);
+        let (other_min, other_max) = project(&other_points, axis);
+        let swept = relative_sweep.dot(axis);
+
+        if max + swept.max(0.0) < other_min || min + swept.min(0.0) > other_max {
+            return false;
+        }
+    }
+
+    true
 }
 
-/// sat_collision_half performs half an SAT test (checks angles of one of two rectangles).
-fn sat_collision_half(
-    position: Vec2,
-    other_position: Vec2,
-    mut axis_normal: Vec2,
-    other_axis_normal: Vec2,
+/// swept_collision performs continuous collision of a projectile against a convex polygon (hull),
+/// by sweeping the projectile (approximated as a line segment from tail to nose) along its path
+/// relative to the polygon. Unlike `radius_collision` and `sat_collision`, large sweeps don't
+/// produce false positives, so the result doesn't depend on delta_seconds. Returns the point of
+/// impact, if any.
+pub fn swept_collision(
+    transform: Transform,
     dimensions: Vec2,
-    other_dimensions: Vec2,
-) -> bool {
-    let other_axis_tangent = other_axis_normal.perp();
-
-    let other_ps: [Vec2; 4] = [
-        other_position
-            + other_axis_normal * other_dimensions.x
-            + other_axis_tangent * other_dimensions.y,
-        other_position + other_axis_normal * other_dimensions.x
-            - other_axis_tangent * other_dimensions.y,
-        other_position
-            - other_axis_normal * other_dimensions.x
-            - other_axis_tangent * other_dimensions.y,
-        other_position - other_axis_normal * other_dimensions.x
-            + other_axis_tangent * other_dimensions.y,
-    ];
-
-    for f in 0..4 {
-        let dimension = if f % 2 == 0 {
-            dimensions.x
-        } else {
-            dimensions.y
-        };
+    other_transform: Transform,
+    other_hull: &[Vec2],
+    delta_seconds: f32,
+) -> Option<Vec2> {
+    let axis_normal = transform.direction.to_vec();
+    let other_velocity = other_transform.direction.to_vec() * other_transform.velocity.to_mps();
+    let relative_sweep =
+        (axis_normal * transform.velocity.to_mps() - other_velocity) * delta_seconds;
 
-        let dot = position.dot(axis_normal);
+    // From the tail, at the start, to the nose, at the end.
+    let start = transform.position - axis_normal * (dimensions.x * 0.5);
+    let end = transform.position + axis_normal * (dimensions.x * 0.5) + relative_sweep;
+
+    // Account for the projectile's width by enlarging the polygon.
+    let fraction = segment_entry(
+        other_transform.to_local(start),
+        other_transform.to_local(end),
+        other_hull,
+        dimensions.y * 0.5,
+    )?;
+
+    // Convert back from relative to absolute motion.
+    Some(start + (end - start) * fraction + other_velocity * (delta_seconds * fraction))
+}
 
-        // Dimension is always positive, so min < max.
-        let min = dot - dimension;
-        let max = dot + dimension;
+/// segment_entry returns the fraction of the way from start to end at which a line segment first
+/// enters a counter-clockwise convex polygon, enlarged by a margin, if it does at all (Cyrus-Beck).
+fn segment_entry(start: Vec2, end: Vec2, polygon: &[Vec2], margin: f32) -> Option<f32> {
+    let delta = end - start;
+    let mut entry = 0f32;
+    let mut exit = 1f32;
 
-        let mut less = true;
-        let mut greater = true;
+    for (i, &vertex) in polygon.iter().enumerate() {
+        let edge = polygon[(i + 1) % polygon.len()] - vertex;
+        let outward = -edge.perp().normalize_or_zero();
 
-        for other_p in other_ps {
-            let d = other_p.dot(axis_normal);
-            less &= d < min;
-            greater &= d > max;
+        // Segment is inside this edge where distance + t * approach <= 0.
+        let distance = outward.dot(start - vertex) - margin;
+        let approach = outward.dot(delta);
+
+        if approach.abs() < f32::EPSILON {
+            // Parallel to this edge, so either always or never inside it.
+            if distance > 0.0 {
+                return None;
+            }
+            continue;
         }
 
-        if less || greater {
-            return false;
+        let t = -distance / approach;
+        if approach < 0.0 {
+            entry = entry.max(t);
+        } else {
+            exit = exit.min(t);
         }
 
-        // Start over with next axis.
-        axis_normal = axis_normal.perp();
+        if entry > exit {
+            return None;
+        }
     }
 
-    true
+    Some(entry)
+}
+
+/// edge_normals returns the (not normalized) normals of each edge of a polygon.
+fn edge_normals(points: &[Vec2]) -> impl Iterator<Item = Vec2> + '_ {
+    (0..points.len()).map(move |i| (points[(i + 1) % points.len()] - points[i]).perp())
+}
+
+/// project returns the minimum and maximum of the dot products of points with an axis.
+fn project(points: &[Vec2], axis: Vec2) -> (f32, f32) {
+    points
+        .iter()
+        .map(|p| p.dot(axis))
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), d| {
+            (min.min(d), max.max(d))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::collision::{radius_collision, sat_collision, swept_collision};
+    use common::angle::Angle;
+    use common::transform::Transform;
+    use common::velocity::Velocity;
+    use glam::Vec2;
+
+    #[test]
+    fn swept_collision_tick_rate() {
+        let projectile = Transform {
+            position: Vec2::ZERO,
+            direction: Angle::ZERO,
+            velocity: Velocity::from_mps(900.0),
+        };
+        let projectile_dimensions = Vec2::new(4.0, 0.5);
+        let target_hull = [
+            Vec2::new(-5.0, -2.0),
+            Vec2::new(5.0, -2.0),
+            Vec2::new(5.0, 2.0),
+            Vec2::new(-5.0, 2.0),
+        ];
+
+        // A thin target, in the path, is hit (on its near side) no matter the tick rate.
+        let target = Transform::from_position(Vec2::new(60.0, 0.0));
+        for delta_seconds in [0.1, 0.2, 0.5] {
+            let impact = swept_collision(
+                projectile,
+                projectile_dimensions,
+                target,
+                &target_hull,
+                delta_seconds,
+            )
+            .unwrap();
+            assert!((impact.x - 54.75).abs() < 0.01, "{:?}", impact);
+        }
+
+        // A target to the side of the path is only hit according to the inflated radius.
+        let target = Transform::from_position(Vec2::new(60.0, 20.0));
+        assert!(
+            swept_collision(projectile, projectile_dimensions, target, &target_hull, 0.1).is_none()
+        );
+        assert!(radius_collision(projectile, 2.0, target, 5.4, 0.1));
+
+        // A target behind the projectile is never hit.
+        let target = Transform::from_position(Vec2::new(-60.0, 0.0));
+        assert!(
+            swept_collision(projectile, projectile_dimensions, target, &target_hull, 0.1).is_none()
+        );
+    }
+
+    #[test]
+    fn sat_collision_hull() {
+        let rectangle = [
+            Vec2::new(-5.0, -2.0),
+            Vec2::new(5.0, -2.0),
+            Vec2::new(5.0, 2.0),
+            Vec2::new(-5.0, 2.0),
+        ];
+        let pointed = [
+            Vec2::new(-5.0, -2.0),
+            Vec2::new(2.0, -2.0),
+            Vec2::new(5.0, 0.0),
+            Vec2::new(2.0, 2.0),
+            Vec2::new(-5.0, 2.0),
+        ];
+        let square = [
+            Vec2::new(-0.5, -0.5),
+            Vec2::new(0.5, -0.5),
+            Vec2::new(0.5, 0.5),
+            Vec2::new(-0.5, 0.5),
+        ];
+
+        // Next to the bow, where only a rectangle would collide.
+        let boat = Transform::new();
+        let other = Transform::from_position(Vec2::new(4.6, 1.6));
+        assert!(sat_collision(
+            boat, &rectangle, 5.4, other, &square, 0.7, 0.0
+        ));
+        assert!(!sat_collision(
+            boat, &pointed, 5.4, other, &square, 0.7, 0.0
+        ));
+
+        // Unless the boat moves forward into it.
+        let boat = Transform {
+            velocity: Velocity::from_mps(10.0),
+            ..boat
+        };
+        assert!(sat_collision(boat, &pointed, 5.4, other, &square, 0.7, 0.5));
+    }
 }
@@ -8,7 +8,9 @@ use crate::world::World;
 use atomic_refcell::AtomicRef;
 use common::complete::CompleteTrait;
 use common::contact::ContactTrait;
+use common::current::Currents;
 use common::death_reason::DeathReason;
+use common::harbor::Harbor;
 use common::protocol::Update;
 use common::terrain;
 use common::terrain::{ChunkSet, Terrain};
@@ -45,6 +47,9 @@ impl<'a, I: Iterator<Item = ContactRef<'a>>> CompleteRef<'a, I> {
         }
     }
 
+    /// How often to send ocean currents.
+    const CURRENTS_PERIOD: Ticks = Ticks::FREQUENCY_HZ;
+
     pub fn into_update(self, counter: Ticks, loaded_chunks: &mut ChunkSet) -> Update {
         let death_reason = if let Status::Dead { reason, .. } = &self.player.data.status {
             Some(reason.clone())
@@ -85,6 +90,51 @@ impl<'a, I: Iterator<Item = ContactRef<'a>>> CompleteRef<'a, I> {
 
         *loaded_chunks = new_loaded_chunks;
 
+        // Only send anti-aircraft gunfire that is on screen.
+        let half_camera_dims = self.camera_dims * 0.5;
+        let anti_aircraft = self
+            .world
+            .anti_aircraft_fire
+            .iter()
+            .filter(|(position, _)| {
+                (*position - self.camera_pos)
+                    .abs()
+                    .cmple(half_camera_dims)
+                    .all()
+            })
+            .map(|(_, fire)| fire.clone())
+            .collect();
+
+        // Only send harbors that are (at least partially) on screen.
+        let harbors = self
+            .world
+            .harbors
+            .iter()
+            .filter(|harbor| {
+                (harbor.position - self.camera_pos)
+                    .abs()
+                    .cmple(half_camera_dims + Vec2::splat(Harbor::RADIUS))
+                    .all()
+            })
+            .copied()
+            .collect();
+
+        let player_id = self.player.player_id;
+        let events = self
+            .world
+            .events
+            .iter()
+            .filter(|(event_player_id, _)| *event_player_id == player_id)
+            .map(|(_, event)| event.clone())
+            .collect();
+
+        // Currents vary slowly, so only send them once in a while.
+        let currents = (counter % Self::CURRENTS_PERIOD == Ticks::ZERO).then(|| {
+            Currents::new(self.camera_pos, self.camera_dims, |position| {
+                self.world.current_at(position)
+            })
+        });
+
         Update {
             contacts: self
                 .contacts
@@ -113,6 +163,11 @@ impl<'a, I: Iterator<Item = ContactRef<'a>>> CompleteRef<'a, I> {
             score: self.player.score,
             world_radius: self.world.radius,
             terrain,
+            anti_aircraft,
+            currents,
+            harbors,
+            storm: self.world.storm,
+            events,
         }
     }
 }
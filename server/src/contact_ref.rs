@@ -2,12 +2,14 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use crate::entity::Entity;
+use common::affliction::Afflictions;
 use common::altitude::Altitude;
 use common::angle::Angle;
 use common::contact::{Contact, ContactTrait, ANGLE_ARRAY_ZERO, TICKS_ARRAY_ZERO};
 use common::entity::EntityId;
 use common::entity::EntityType;
 use common::guidance::Guidance;
+use common::subsystems::Subsystems;
 use common::ticks::Ticks;
 use common::transform::Transform;
 use core_protocol::id::PlayerId;
@@ -19,29 +21,40 @@ pub struct ContactRef<'a> {
     visible: bool,
     known: bool,
     has_type: bool,
+    owned: bool,
 }
 
 impl<'a> ContactRef<'a> {
     /// Creates a new `ContactRef`, referencing an entity, and having certain visibility parameters.
-    pub fn new(entity: &'a Entity, visible: bool, known: bool, has_type: bool) -> Self {
+    pub fn new(
+        entity: &'a Entity,
+        visible: bool,
+        known: bool,
+        has_type: bool,
+        owned: bool,
+    ) -> Self {
         Self {
             entity,
             visible,
             known,
             has_type,
+            owned,
         }
     }
 
     /// Converts into a non-ref `Contact`.
     pub fn into_contact(self) -> Contact {
         Contact::new(
+            self.afflictions(),
             self.altitude(),
             self.damage(),
             self.entity_type(),
             *self.guidance(),
             self.id(),
+            self.loadout(),
             self.player_id(),
             self.reloads_arc().cloned(),
+            self.subsystems().cloned(),
             *self.transform(),
             self.turrets_arc().cloned(),
         )
@@ -65,6 +78,15 @@ impl<'a> ContactRef<'a> {
 }
 
 impl<'a> ContactTrait for ContactRef<'a> {
+    #[inline]
+    fn afflictions(&self) -> Afflictions {
+        if self.has_type && self.entity.is_boat() {
+            self.entity.extension().afflictions()
+        } else {
+            Afflictions::default()
+        }
+    }
+
     #[inline]
     fn altitude(&self) -> Altitude {
         self.entity.altitude
@@ -99,6 +121,15 @@ impl<'a> ContactTrait for ContactRef<'a> {
         self.entity.id
     }
 
+    #[inline]
+    fn loadout(&self) -> u8 {
+        if self.has_type && self.entity.is_boat() {
+            self.entity.extension().loadout
+        } else {
+            0
+        }
+    }
+
     #[inline]
     fn player_id(&self) -> Option<PlayerId> {
         self.entity
@@ -117,6 +148,15 @@ impl<'a> ContactTrait for ContactRef<'a> {
         self.has_type && self.entity.is_boat() && (self.visible || self.known)
     }
 
+    #[inline]
+    fn subsystems(&self) -> Option<&Subsystems> {
+        if self.owned && self.entity.is_boat() {
+            Some(&self.entity.extension().subsystems)
+        } else {
+            None
+        }
+    }
+
     #[inline]
     fn transform(&self) -> &Transform {
         &self.entity.transform
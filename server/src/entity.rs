@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use crate::arena::Arena;
-use crate::collision::{radius_collision, sat_collision};
+use crate::collision::{radius_collision, sat_collision, swept_collision};
 use crate::entities::*;
 use crate::entity_extension::EntityExtension;
 use crate::player::*;
@@ -10,6 +10,7 @@ use crate::server::Server;
 use atomic_refcell::{AtomicRef, AtomicRefMut};
 use common::altitude::Altitude;
 use common::angle::Angle;
+use common::consumable::Consumable;
 use common::death_reason::DeathReason;
 use common::entity::*;
 use common::guidance::Guidance;
@@ -76,8 +77,9 @@ impl Entity {
         unsafe { &mut *self.player.as_ref().unwrap().extension.0.get() }
     }
 
-    /// change_entity_type is the only valid way to change an entity's type.
-    pub fn change_entity_type(&mut self, entity_type: EntityType, arena: &mut Arena) {
+    /// change_entity_type is the only valid way to change an entity's type. Boats are armed with the
+    /// given loadout, which must be valid for the new type.
+    pub fn change_entity_type(&mut self, entity_type: EntityType, loadout: u8, arena: &mut Arena) {
         let old_data = self.data();
         debug_assert_eq!(old_data.kind, entity_type.data().kind);
 
@@ -110,26 +112,35 @@ impl Entity {
         // Save some settings from the old extension.
         let old_active = extension.active;
         let old_altitude_target = extension.altitude_target;
+        let old_consumable_effects = std::mem::take(&mut extension.consumable_effects);
+        let old_fire = std::mem::take(&mut extension.fire);
+        let old_flooding = std::mem::take(&mut extension.flooding);
+        let old_damage_contributors = std::mem::take(&mut extension.damage_contributors);
 
         // Keep armament (lack of) reloads. Use usize ot avoid overflow.
         // Start by counting the total ticks left to reload (for non-limited armaments).
         let mut total_reload = 0;
+        let old_armaments = old_data.loadout_armaments(extension.loadout);
         for (i, reload) in extension.reloads.iter().enumerate() {
-            if !old_data.armaments[i].entity_type.data().limited {
+            if !old_armaments[i].entity_type.data().limited {
                 total_reload += reload.0 as usize;
             }
         }
 
         // Change the extension to correspond with the new type.
-        *extension = EntityExtension::new(entity_type);
+        *extension = EntityExtension::new(entity_type, loadout);
 
         // Restore some settings from the old extension.
         extension.active = old_active;
         extension.altitude_target = old_altitude_target;
+        extension.consumable_effects = old_consumable_effects;
+        extension.fire = old_fire;
+        extension.flooding = old_flooding;
+        extension.damage_contributors = old_damage_contributors;
 
         // Finish (un)reloading.
         for (i, reload) in extension.reloads_mut().iter_mut().enumerate() {
-            let armament = &new_data.armaments[i];
+            let armament = &new_data.loadout_armaments(loadout)[i];
             if !armament.entity_type.data().limited {
                 let to_consume = (armament.reload().0 as usize).min(total_reload);
                 *reload = Ticks(to_consume as TicksRepr);
@@ -158,7 +169,12 @@ impl Entity {
         } else {
             return;
         }
-        *self.extension_mut() = EntityExtension::new(self.entity_type);
+
+        // Keep the loadout chosen when spawning, unless it isn't valid for this entity type.
+        let loadout = Some(self.extension().loadout)
+            .filter(|&l| self.data().is_valid_loadout(l))
+            .unwrap_or(0);
+        *self.extension_mut() = EntityExtension::new(self.entity_type, loadout);
     }
 
     /// Adjusts player's pointer to self, if applicable.
@@ -217,6 +233,11 @@ impl Entity {
         self.entity_type.data()
     }
 
+    /// Gets the armaments of the boat's loadout.
+    pub fn armaments(&self) -> &'static [Armament] {
+        self.data().loadout_armaments(self.extension().loadout)
+    }
+
     /// Returns true if and only if the entity is of kind boat.
     pub fn is_boat(&self) -> bool {
         self.entity_type.data().kind == EntityKind::Boat
@@ -246,19 +267,35 @@ impl Entity {
             && other.altitude.is_submerged()
         {
             self.is_in_proximity_to(other, EntityData::DEPTH_CHARGE_PROXIMITY)
+        } else if data.kind == EntityKind::Weapon && other_data.kind == EntityKind::Boat {
+            self.impact_with(other, delta_seconds).is_some()
+        } else if data.kind == EntityKind::Boat && other_data.kind == EntityKind::Weapon {
+            other.impact_with(self, delta_seconds).is_some()
         } else {
             sat_collision(
                 self.transform,
-                data.dimensions(),
+                &data.hull,
                 data.radius,
                 other.transform,
-                other_data.dimensions(),
+                &other_data.hull,
                 other_data.radius,
                 delta_seconds,
             )
         }
     }
 
+    /// Determines where a projectile (self) would first hit another entity if delta_seconds
+    /// elapsed, if at all. Unlike `collides_with` in general, this is exact for large sweeps.
+    pub fn impact_with(&self, other: &Self, delta_seconds: f32) -> Option<Vec2> {
+        swept_collision(
+            self.transform,
+            self.data().dimensions(),
+            other.transform,
+            &other.data().hull,
+            delta_seconds,
+        )
+    }
+
     /// Combines transform and dimensions.
     pub fn dimension_transform(&self) -> DimensionTransform {
         DimensionTransform {
@@ -296,6 +333,19 @@ impl Entity {
             Altitude::ZERO
         };
 
+        if self.data().kind == EntityKind::Weapon {
+            // Projectiles are thin and fast, so sweep them (from tail to where their nose will be)
+            // to avoid skipping over narrow land or clipping corners.
+            let normal = self.transform.direction.to_vec();
+            let half_length = self.data().length * 0.5;
+            let sweep = self.transform.velocity.to_mps() * delta_seconds;
+            return t.collides_with_segment(
+                self.transform.position - normal * half_length,
+                self.transform.position + normal * (half_length + sweep),
+                self.altitude.max(threshold),
+            );
+        }
+
         // If submerged, colliding with terrain should be relatively temporary (boats should simply
         // rise up rather than taking damage) so it is ignored.
         t.collides_with(
@@ -324,7 +374,7 @@ impl Entity {
 
     /// Marks a particular armament as consumed.
     pub fn consume_armament(&mut self, index: usize) {
-        let a = &self.data().armaments[index];
+        let a = &self.armaments()[index];
 
         // Limited armaments start their timer when they die.
         let reload = if a.entity_type.data().limited {
@@ -343,14 +393,42 @@ impl Entity {
         self.extension_mut().reloads_mut()[index] = reload;
     }
 
-    /// Repairs by a certain amount, up to maximum health.
+    /// Repairs by a certain amount, up to maximum health. Also repairs subsystems.
     pub fn repair(&mut self, amount: Ticks) {
         self.ticks = self.ticks.saturating_sub(amount);
+        self.extension_mut().subsystems.repair(amount);
+    }
+
+    /// Applies the effects of a consumable to a boat.
+    pub fn consume(&mut self, consumable: Consumable) {
+        let data = consumable.data();
+
+        if data.repair > 0.0 {
+            self.repair(self.data().max_health() * data.repair);
+        }
+
+        let extension = self.extension_mut();
+
+        if data.extinguish {
+            extension.fire.clear();
+            extension.flooding.clear();
+        }
+
+        if data.reload {
+            for reload in extension.reloads_mut() {
+                // Deployed limited armaments are reloaded when they are destroyed.
+                if *reload != Ticks::MAX {
+                    *reload = Ticks::ZERO;
+                }
+            }
+        }
+
+        extension.consumable_effects.add(consumable);
     }
 
     /// Reloads arbitrary armaments/groups by a certain amount.
     pub fn reload(&mut self, amount: Ticks) {
-        let armaments = &self.data().armaments;
+        let armaments = self.armaments();
         let extension = self.extension_mut();
         let reloads = extension.reloads_mut();
         if reloads.is_empty() {
@@ -418,9 +496,14 @@ impl Entity {
         self.ticks.saturating_add(damage) >= self.data().max_health()
     }
 
+    /// Returns the damage that would ultimately kill an entity in kill_time, assuming delta ticks elapsed.
+    pub fn damage_in(&self, delta: Ticks, kill_time: Ticks) -> Ticks {
+        delta * (self.data().max_health() / kill_time).max(Ticks::ONE)
+    }
+
     /// Apply damage to ultimately kill an entity in kill_time, assuming delta ticks elapsed. Returns true if now dead.
     pub fn kill_in(&mut self, delta: Ticks, kill_time: Ticks) -> bool {
-        self.damage(delta * (self.data().max_health() / kill_time).max(Ticks::ONE))
+        self.damage(self.damage_in(delta, kill_time))
     }
 
     /// Returns true if the entity obeys special altitude mechanics (overlaps a wider altitude range),
@@ -479,6 +562,7 @@ impl Entity {
                 _ => Altitude::ZERO,
             },
             EntityKind::Decoy => match data.sub_kind {
+                EntitySubKind::Radar => Altitude::MAX,
                 EntitySubKind::Sonar => Altitude::MIN,
                 _ => Altitude::ZERO,
             },
@@ -524,6 +608,8 @@ impl Entity {
                 }
             },
             EntityKind::Decoy => match data.sub_kind {
+                // Chaff hangs at the altitude of sea-skimming missiles.
+                EntitySubKind::Radar => unguided_weapon_altitude,
                 EntitySubKind::Sonar => -unguided_weapon_altitude,
                 _ => {
                     debug_assert!(false, "{:?}", data.sub_kind);
@@ -545,6 +631,36 @@ impl Entity {
         altitude_change
     }
 
+    /// Moves a ballistic shell along its arc, climbing after launch and descending so as to reach
+    /// sea level as its lifespan expires. Returns amount altitude changed by.
+    pub fn apply_ballistic_arc(&mut self, delta: Ticks) -> Altitude {
+        let data = self.data();
+        debug_assert!(data.is_ballistic());
+
+        let remaining = data.lifespan.saturating_sub(self.ticks);
+        let climbing =
+            self.altitude.to_meters() + delta.to_secs() * EntityData::BALLISTIC_VERTICAL_SPEED;
+        let descending = remaining.to_secs() * EntityData::BALLISTIC_VERTICAL_SPEED;
+
+        let altitude = Altitude::from_meters(climbing.min(descending));
+        let altitude_change = altitude - self.altitude;
+        self.altitude = altitude;
+        altitude_change
+    }
+
+    /// Returns true if and only if the entity is a boat whose jammer is on and covers position.
+    pub fn is_jamming(&self, position: Vec2) -> bool {
+        self.is_boat()
+            && self.extension().is_active()
+            && !self.altitude.is_submerged()
+            && self
+                .data()
+                .sensors
+                .jammer
+                .as_ref()
+                .map_or(false, |jammer| jammer.covers(&self.transform, position))
+    }
+
     /// Returns true if and only if two entities are friendly i.e. same player or same team.
     pub fn is_friendly(&self, other: &Self) -> bool {
         self.is_friendly_to_player(other.player.as_deref())
@@ -564,6 +680,10 @@ impl Entity {
         let player = player.borrow_player();
         let other_player = other_player.borrow_player();
 
+        if player.data.faction.is_some() && player.data.faction == other_player.data.faction {
+            return true;
+        }
+
         if player.team_id.is_none() || other_player.team_id.is_none() {
             return false;
         }
@@ -584,7 +704,7 @@ impl Entity {
         let data = self.data();
         let boat_data = boat.data();
         let boat_extension = boat.extension();
-        for (i, armament) in boat_data.armaments.iter().enumerate() {
+        for (i, armament) in boat.armaments().iter().enumerate() {
             if armament.entity_type != self.entity_type {
                 // Irrelevant armament.
                 continue;
@@ -605,8 +725,8 @@ impl Entity {
                 continue;
             }
 
-            let transform =
-                boat.transform + boat.data().armament_transform(&boat_extension.turrets, i);
+            let transform = boat.transform
+                + boat_data.armament_transform(boat_extension.loadout, &boat_extension.turrets, i);
             if self.transform.position.distance_squared(transform.position) < data.radius.powi(2) {
                 // Helicopters can land at any angle, but planes must be withing angle parameters.
                 if data.sub_kind == EntitySubKind::Heli
@@ -623,6 +743,68 @@ impl Entity {
     /// sensor ranges.
     pub const CLOSE_PROXIMITY: f32 = 60.0;
 
+    /// Maximum speed (in mps) at which a boat can salvage a wreck.
+    pub const MAX_SALVAGE_SPEED: f32 = 5.0;
+    /// How many times faster a wreck decays while being salvaged.
+    pub const SALVAGE_FACTOR: f32 = 20.0;
+
+    /// Maximum speed (in mps) at which a tanker can resupply a teammate.
+    pub const MAX_RESUPPLY_SPEED: f32 = 5.0;
+    /// Maximum gap (in meters) between a tanker and a teammate it is resupplying.
+    pub const RESUPPLY_DISTANCE: f32 = 25.0;
+    /// How many times faster than normal a resupplied teammate repairs and reloads.
+    pub const RESUPPLY_FACTOR: f32 = 2.0;
+    /// Score a tanker earns per second of damage it repairs. Reloading alone earns nothing, since
+    /// a teammate that fires non-stop always needs it.
+    pub const RESUPPLY_SCORE: f32 = 0.5;
+
+    /// needs_resupply returns true if and only if self, a boat, is damaged or reloading.
+    pub fn needs_resupply(&self) -> bool {
+        self.ticks > Ticks::ZERO
+            || self
+                .extension()
+                .reloads
+                .iter()
+                .any(|&reload| reload > Ticks::ZERO && reload != Ticks::MAX)
+    }
+
+    /// can_resupply returns true if and only if self, a tanker, is stationed alongside teammate,
+    /// which needs resupply. Does not check whether they are actually teammates. Non-player
+    /// merchants never resupply their escorts, since their score is tuned by their faction.
+    pub fn can_resupply(&self, teammate: &Self) -> bool {
+        self.data().sub_kind == EntitySubKind::Tanker
+            && !self
+                .player
+                .as_ref()
+                .map_or(false, |player| player.borrow_player().player_id.is_npc())
+            && !teammate.altitude.is_submerged()
+            && self.transform.velocity.abs().to_mps() <= Self::MAX_RESUPPLY_SPEED
+            && teammate.transform.velocity.abs().to_mps() <= Self::MAX_RESUPPLY_SPEED
+            && teammate
+                .is_in_proximity_to(self, teammate.data().width * 0.5 + Self::RESUPPLY_DISTANCE)
+            && teammate.needs_resupply()
+    }
+
+    /// can_salvage returns true if and only if self, a boat, is slowly passing over a wreck and
+    /// is a submerged submarine or a dredger.
+    pub fn can_salvage(&self, wreck: &Self) -> bool {
+        debug_assert_eq!(wreck.data().sub_kind, EntitySubKind::Wreck);
+
+        let salvager = match self.data().sub_kind {
+            EntitySubKind::Submarine => self.altitude.is_submerged(),
+            EntitySubKind::Dredger => true,
+            _ => false,
+        };
+
+        salvager
+            && self.transform.velocity.abs().to_mps() <= Self::MAX_SALVAGE_SPEED
+            && self
+                .transform
+                .position
+                .distance_squared(wreck.transform.position)
+                <= wreck.data().radius.powi(2)
+    }
+
     /// Calculates proximity to a boat (which is defined as the minimum normal or tangential
     /// difference to the boats front or side).
     pub fn proximity_to(&self, boat: &Self) -> f32 {
@@ -669,9 +851,14 @@ impl Eq for Entity {}
 #[cfg(test)]
 mod tests {
     use crate::entity::Entity;
+    use common::altitude::Altitude;
     use common::entity::{EntityId, EntityType};
+    use common::ticks::Ticks;
+    use core_protocol::id::PlayerId;
+    use game_server::context::{PlayerData, PlayerTuple};
     use glam::Vec2;
     use std::mem;
+    use std::sync::Arc;
 
     #[test]
     fn size_of() {
@@ -699,6 +886,44 @@ mod tests {
         );
     }
 
+    #[test]
+    fn can_salvage() {
+        unsafe {
+            EntityType::init();
+        }
+        let wreck = Entity::new(EntityType::WreckMedium, None);
+        let mut submarine = Entity::new(EntityType::Akula, None);
+        assert!(!submarine.can_salvage(&wreck));
+        submarine.altitude = Altitude::from_meters(-20.0);
+        assert!(submarine.can_salvage(&wreck));
+        submarine.transform.position = Vec2::new(200.0, 0.0);
+        assert!(!submarine.can_salvage(&wreck));
+        assert!(!Entity::new(EntityType::Zubr, None).can_salvage(&wreck));
+    }
+
+    #[test]
+    fn can_resupply() {
+        unsafe {
+            EntityType::init();
+        }
+        let tanker_with_player_id = |player_id: PlayerId| {
+            let player = PlayerData::new(player_id, None);
+            Entity::new(EntityType::Tanker, Some(Arc::new(PlayerTuple::new(player))))
+        };
+        let tanker = tanker_with_player_id(PlayerId::nth_bot(0).unwrap());
+        let mut teammate = Entity::new(EntityType::Fletcher, None);
+        teammate.ticks = Ticks::from_secs(10.0);
+        teammate.transform.position = Vec2::new(0.0, 100.0);
+        assert!(!tanker.can_resupply(&teammate));
+        teammate.transform.position = Vec2::new(0.0, 30.0);
+        assert!(tanker.can_resupply(&teammate));
+        assert!(!teammate.can_resupply(&tanker));
+
+        // Convoy merchants don't resupply their escorts.
+        let merchant = tanker_with_player_id(PlayerId::nth_npc(0).unwrap());
+        assert!(!merchant.can_resupply(&teammate));
+    }
+
     #[test]
     fn eq() {
         unsafe {
@@ -1,13 +1,216 @@
 // SPDX-FileCopyrightText: 2021 Softbear, Inc.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use crate::server::Server;
+use common::affliction::{Affliction, Afflictions};
 use common::altitude::Altitude;
 use common::angle::Angle;
+use common::consumable::Consumable;
 use common::entity::*;
+use common::subsystems::Subsystems;
 use common::ticks::Ticks;
-use common::util::make_mut_slice;
+use common::util::{make_mut_slice, split_kill_score};
+use game_server::context::PlayerTuple;
 use std::iter::FromIterator;
-use std::sync::Arc;
+use std::sync::{Arc, Weak};
+
+/// Stacks of an ongoing affliction, such as a fire, and who caused it.
+#[derive(Debug, Default)]
+pub struct AfflictionState {
+    stacks: u8,
+    /// Repair time remaining until the current stack subsides.
+    remaining: Ticks,
+    /// Player and weapon responsible for the most recent stack, to credit with a kill.
+    source: Option<(Weak<PlayerTuple<Server>>, EntityType)>,
+}
+
+impl AfflictionState {
+    const MAX_STACKS: u8 = 3;
+    /// How much repair time it takes for each stack to subside.
+    const DURATION: Ticks = Ticks(Ticks::FREQUENCY_HZ.0 * 8);
+
+    /// Number of stacks, zero if not afflicted.
+    pub fn stacks(&self) -> u8 {
+        self.stacks
+    }
+
+    /// Adds a stack, up to the maximum, caused by a player's weapon.
+    pub fn add(&mut self, source: &Arc<PlayerTuple<Server>>, weapon_type: EntityType) {
+        if self.stacks == 0 {
+            self.remaining = Self::DURATION;
+        }
+        self.stacks = (self.stacks + 1).min(Self::MAX_STACKS);
+        self.source = Some((Arc::downgrade(source), weapon_type));
+    }
+
+    /// Removes a stack, if any.
+    pub fn remove(&mut self) {
+        self.stacks = self.stacks.saturating_sub(1);
+        if self.stacks == 0 {
+            self.remaining = Ticks::ZERO;
+            self.source = None;
+        } else {
+            self.remaining = Self::DURATION;
+        }
+    }
+
+    /// Removes all stacks.
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Spends repair time on putting out the current stack.
+    pub fn subside(&mut self, amount: Ticks) {
+        if self.stacks == 0 {
+            return;
+        }
+        if amount >= self.remaining {
+            self.remove();
+        } else {
+            self.remaining = self.remaining.saturating_sub(amount);
+        }
+    }
+
+    /// Returns the player (if still in the game) and weapon responsible for the affliction.
+    pub fn source(&self) -> Option<(Arc<PlayerTuple<Server>>, EntityType)> {
+        self.source
+            .as_ref()
+            .and_then(|(player, weapon_type)| Some((player.upgrade()?, *weapon_type)))
+    }
+}
+
+/// Damage recently done to a boat by one player.
+#[derive(Debug)]
+struct DamageContribution {
+    player: Weak<PlayerTuple<Server>>,
+    /// Most recent weapon (or boat, if ramming) used.
+    weapon_type: EntityType,
+    /// Total damage done.
+    damage: Ticks,
+    /// Time since damage was last done.
+    age: Ticks,
+}
+
+/// Players who recently damaged a boat, so that they can share the credit if it is sunk.
+#[derive(Debug, Default)]
+pub struct DamageContributors(Vec<DamageContribution>);
+
+impl DamageContributors {
+    /// How long damage counts towards an assist.
+    const DURATION: Ticks = Ticks(Ticks::FREQUENCY_HZ.0 * 30);
+
+    /// Records damage done by a player's weapon.
+    pub fn add(
+        &mut self,
+        player: &Arc<PlayerTuple<Server>>,
+        weapon_type: EntityType,
+        damage: Ticks,
+    ) {
+        if damage == Ticks::ZERO {
+            // Such as a friendly collision.
+            return;
+        }
+
+        let i = if let Some(i) = self
+            .0
+            .iter()
+            .position(|c| c.player.as_ptr() == Arc::as_ptr(player))
+        {
+            i
+        } else {
+            self.0.push(DamageContribution {
+                player: Arc::downgrade(player),
+                weapon_type,
+                damage: Ticks::ZERO,
+                age: Ticks::ZERO,
+            });
+            self.0.len() - 1
+        };
+
+        let contribution = &mut self.0[i];
+        contribution.weapon_type = weapon_type;
+        contribution.damage = contribution.damage.saturating_add(damage);
+        contribution.age = Ticks::ZERO;
+    }
+
+    /// Forgets damage that is no longer recent, or was done by players who left the game.
+    pub fn update(&mut self, delta: Ticks) {
+        for contribution in self.0.iter_mut() {
+            contribution.age = contribution.age.saturating_add(delta);
+        }
+        self.0
+            .retain(|c| c.age < Self::DURATION && c.player.strong_count() > 0);
+    }
+
+    /// Splits the score for sinking the boat between the killer and any other players who
+    /// recently damaged it. Returns the killer's score, followed by each assist (along with the
+    /// weapon they used).
+    pub fn split(
+        &self,
+        killer: &Arc<PlayerTuple<Server>>,
+        score: u32,
+    ) -> (u32, Vec<(Arc<PlayerTuple<Server>>, EntityType, u32)>) {
+        let assists: Vec<_> = self
+            .0
+            .iter()
+            .filter(|c| c.player.as_ptr() != Arc::as_ptr(killer))
+            .filter_map(|c| Some((c.player.upgrade()?, c.weapon_type, c.damage)))
+            .collect();
+
+        let damages: Vec<f32> = assists.iter().map(|(_, _, d)| d.to_secs()).collect();
+        let (killer_score, assist_scores) = split_kill_score(score, &damages);
+
+        (
+            killer_score,
+            assists
+                .into_iter()
+                .zip(assist_scores)
+                .filter(|(_, score)| *score > 0)
+                .map(|((player, weapon_type, _), score)| (player, weapon_type, score))
+                .collect(),
+        )
+    }
+}
+
+/// Lasting effects of consumables bought for a boat, and how long they have left.
+#[derive(Debug, Default)]
+pub struct ConsumableEffects(Vec<(Consumable, Ticks)>);
+
+impl ConsumableEffects {
+    /// Starts (or restarts) the effect of a consumable, if it is lasting.
+    pub fn add(&mut self, consumable: Consumable) {
+        let duration = consumable.data().duration;
+        if duration == Ticks::ZERO {
+            return;
+        }
+        if let Some(i) = self.0.iter().position(|(c, _)| *c == consumable) {
+            self.0[i].1 = duration;
+        } else {
+            self.0.push((consumable, duration));
+        }
+    }
+
+    /// Forgets effects that have worn off.
+    pub fn update(&mut self, delta: Ticks) {
+        for (_, remaining) in self.0.iter_mut() {
+            *remaining = remaining.saturating_sub(delta);
+        }
+        self.0.retain(|(_, remaining)| *remaining > Ticks::ZERO);
+    }
+
+    /// Returns the multiplier of the boat's sensor ranges.
+    pub fn sensor_factor(&self) -> f32 {
+        self.0.iter().map(|(c, _)| c.data().sensor_factor).product()
+    }
+
+    /// Returns the multiplier of the range at which the boat can be seen.
+    pub fn visibility_factor(&self) -> f32 {
+        self.0
+            .iter()
+            .map(|(c, _)| c.data().visibility_factor)
+            .product()
+    }
+}
 
 /// Additional fields for certain entities (for now, boats). Stored separately for memory efficiency.
 #[derive(Debug)]
@@ -20,8 +223,20 @@ pub struct EntityExtension {
     active_cooldown: Ticks,
     /// Ticks of protection ticks remaining, zeroed if showing signs of aggression.
     spawn_protection_remaining: Ticks,
+    /// Ticks remaining of being revealed by nearby splashes.
+    bracketed_remaining: Ticks,
+    /// Which of the boat's loadouts it is armed with, chosen when spawning or upgrading.
+    pub loadout: u8,
     pub reloads: Arc<[Ticks]>,
     pub turrets: Arc<[Angle]>,
+    /// Damage to individual parts of the boat.
+    pub subsystems: Subsystems,
+    pub fire: AfflictionState,
+    pub flooding: AfflictionState,
+    /// Players who recently damaged the boat.
+    pub damage_contributors: DamageContributors,
+    /// Lasting effects of consumables.
+    pub consumable_effects: ConsumableEffects,
 }
 
 fn arc_default_n<T: Default>(n: usize) -> Arc<[T]> {
@@ -31,21 +246,30 @@ fn arc_default_n<T: Default>(n: usize) -> Arc<[T]> {
 impl EntityExtension {
     /// How long spawn protection lasts (it linearly fades over this time).
     const SPAWN_PROTECTION_INITIAL: Ticks = Ticks(Ticks::FREQUENCY_HZ.0 * 20);
+    /// How long a boat stays revealed after a nearby splash.
+    const BRACKETED_DURATION: Ticks = Ticks(Ticks::FREQUENCY_HZ.0 * 3);
 
-    /// new allocates a new entity extension, sized to a particular entity type.
-    pub fn new(entity_type: EntityType) -> Self {
+    /// new allocates a new entity extension, sized to a particular entity type and loadout.
+    pub fn new(entity_type: EntityType, loadout: u8) -> Self {
         let data = entity_type.data();
         Self {
             altitude_target: Altitude::ZERO,
             active: true,
             active_cooldown: Ticks::ZERO,
+            bracketed_remaining: Ticks::ZERO,
             spawn_protection_remaining: if entity_type.data().level == 1 {
                 Self::SPAWN_PROTECTION_INITIAL
             } else {
                 Ticks::ZERO
             },
-            reloads: arc_default_n(data.armaments.len()),
+            loadout,
+            reloads: arc_default_n(data.loadout_armaments(loadout).len()),
             turrets: Arc::from_iter(data.turrets.iter().map(|t| t.angle)),
+            subsystems: Subsystems::new(entity_type, loadout),
+            fire: AfflictionState::default(),
+            flooding: AfflictionState::default(),
+            damage_contributors: DamageContributors::default(),
+            consumable_effects: ConsumableEffects::default(),
         }
     }
 
@@ -74,10 +298,40 @@ impl EntityExtension {
         self.spawn_protection_remaining = Ticks::ZERO;
     }
 
-    /// Subtracts from the active cooldown and spawn protection until they reach zero.
-    pub fn update_active_cooldown_and_spawn_protection(&mut self, delta: Ticks) {
+    /// Reveals the boat for a while, due to a nearby splash.
+    pub fn bracket(&mut self) {
+        self.bracketed_remaining = Self::BRACKETED_DURATION;
+    }
+
+    /// Returns whether the boat was recently revealed by a nearby splash.
+    pub fn is_bracketed(&self) -> bool {
+        self.bracketed_remaining > Ticks::ZERO
+    }
+
+    /// Subtracts from the active cooldown, spawn protection, and bracketing until they reach zero,
+    /// and forgets old damage contributions and worn off consumable effects.
+    pub fn update_timers(&mut self, delta: Ticks) {
+        self.damage_contributors.update(delta);
+        self.consumable_effects.update(delta);
         self.active_cooldown = self.active_cooldown.saturating_sub(delta);
         self.spawn_protection_remaining = self.spawn_protection_remaining.saturating_sub(delta);
+        self.bracketed_remaining = self.bracketed_remaining.saturating_sub(delta);
+    }
+
+    /// affliction_mut returns a mutable reference to the state of a particular affliction.
+    pub fn affliction_mut(&mut self, affliction: Affliction) -> &mut AfflictionState {
+        match affliction {
+            Affliction::Fire => &mut self.fire,
+            Affliction::Flooding => &mut self.flooding,
+        }
+    }
+
+    /// afflictions returns which afflictions are visible to other players.
+    pub fn afflictions(&self) -> Afflictions {
+        Afflictions {
+            burning: self.fire.stacks() > 0,
+            flooding: self.flooding.stacks() > 0,
+        }
     }
 
     /// reloads_mut returns a mutable reference to the reloads component of the extension.
@@ -98,9 +352,16 @@ impl Default for EntityExtension {
             altitude_target: Altitude::ZERO,
             active: true,
             spawn_protection_remaining: Self::SPAWN_PROTECTION_INITIAL,
+            bracketed_remaining: Ticks::ZERO,
             active_cooldown: Ticks::ZERO,
+            loadout: 0,
             reloads: arc_default_n(0),
             turrets: arc_default_n(0),
+            subsystems: Subsystems::default(),
+            fire: AfflictionState::default(),
+            flooding: AfflictionState::default(),
+            damage_contributors: DamageContributors::default(),
+            consumable_effects: ConsumableEffects::default(),
         }
     }
 }
@@ -0,0 +1,320 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::npc::{Faction, NpcGroupId};
+use crate::world::World;
+use common::angle::Angle;
+use common::entity::EntityType;
+use common::storm::Storm;
+use common::ticks::Ticks;
+use common::util::gen_radius;
+use common::velocity::Velocity;
+use glam::Vec2;
+use lazy_static::lazy_static;
+use log::warn;
+use rand::seq::SliceRandom;
+use rand::{thread_rng, Rng};
+use serde::Deserialize;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+lazy_static! {
+    /// Events that may be scheduled, which live-ops can edit to run specials.
+    static ref SCHEDULED_EVENTS: ScheduledEvents =
+        serde_json::from_str(include_str!("./scheduled_events.json"))
+            .expect("could not parse scheduled events json");
+}
+
+#[derive(Deserialize)]
+struct ScheduledEvents {
+    /// Seconds between the end of one event and the start of the next.
+    interval: u32,
+    events: Vec<ScheduledEvent>,
+}
+
+/// ScheduledEvent is an entry in the data file, describing when and how an event may occur.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ScheduledEvent {
+    kind: EventKind,
+    /// Relative likelihood of being chosen, among events that are ready.
+    weight: f32,
+    /// Minimum seconds between the start of one occurrence and the start of the next.
+    cooldown: u32,
+    /// Seconds the event lasts.
+    duration: u32,
+    /// UTC days of the week (0 is Sunday) on which the event may occur. Empty means any day.
+    #[serde(default)]
+    days: Vec<u8>,
+    /// Announced when the event starts, in which "{direction}" is replaced by the compass
+    /// direction of the event from the center of the world.
+    announcement: String,
+    /// Announced when the event ends, unless it was cut short (e.g. the treasure fleet was sunk).
+    #[serde(default)]
+    conclusion: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum EventKind {
+    /// A laden convoy crosses the world.
+    TreasureFleet,
+    /// Crates rain down in one area.
+    SupplyDrop,
+    /// A storm front crosses the world, reducing the range of visual and radar sensors.
+    Storm,
+}
+
+/// An event in progress.
+struct ActiveEvent {
+    /// Index into SCHEDULED_EVENTS.events.
+    index: usize,
+    /// Seconds until the event ends.
+    remaining: u32,
+    position: Vec2,
+    /// Non-player group that the event revolves around, if any, such as the treasure fleet.
+    group: Option<NpcGroupId>,
+}
+
+/// EventScheduler periodically triggers world events, chosen by weight from those whose cooldowns
+/// have expired.
+pub struct EventScheduler {
+    /// Seconds until another event may start.
+    countdown: u32,
+    /// Seconds until each event, by index, may start again.
+    cooldowns: Vec<u32>,
+    active: Option<ActiveEvent>,
+}
+
+impl Default for EventScheduler {
+    fn default() -> Self {
+        Self {
+            countdown: SCHEDULED_EVENTS.interval,
+            cooldowns: vec![0; SCHEDULED_EVENTS.events.len()],
+            active: None,
+        }
+    }
+}
+
+impl EventScheduler {
+    /// Seconds to wait before trying again, if no event could start.
+    const RETRY: u32 = 30;
+    /// Crates dropped per second during a supply drop.
+    const SUPPLY_DROP_RATE: usize = 4;
+    /// Radius of the area crates are dropped in.
+    const SUPPLY_DROP_RADIUS: f32 = 150.0;
+
+    /// update starts, continues, and ends events. Should be called once per second.
+    pub fn update(&mut self, world: &mut World) {
+        for cooldown in &mut self.cooldowns {
+            *cooldown = cooldown.saturating_sub(1);
+        }
+
+        if let Some(active) = &mut self.active {
+            active.remaining = active.remaining.saturating_sub(1);
+            if active
+                .group
+                .map_or(false, |group| !world.npcs.group_exists(group))
+            {
+                // Sunk in its entirety, so there is no reason to hold up other events.
+                active.remaining = 0;
+            }
+            let event = &SCHEDULED_EVENTS.events[active.index];
+            Self::continue_event(world, event.kind, active.position);
+
+            if active.remaining == 0 {
+                if Self::end_event(world, event.kind, active.group) {
+                    if let Some(conclusion) = &event.conclusion {
+                        world.announcements.push((conclusion.clone(), Vec::new()));
+                    }
+                }
+                self.active = None;
+                self.countdown = SCHEDULED_EVENTS.interval;
+            }
+            return;
+        }
+
+        self.countdown = self.countdown.saturating_sub(1);
+        if self.countdown > 0 {
+            return;
+        }
+
+        let day = weekday(SystemTime::now());
+        let cooldowns = &self.cooldowns;
+        let ready: Vec<usize> = SCHEDULED_EVENTS
+            .events
+            .iter()
+            .enumerate()
+            .filter(|(i, event)| {
+                cooldowns[*i] == 0
+                    && event.weight > 0.0
+                    && (event.days.is_empty() || event.days.contains(&day))
+            })
+            .map(|(i, _)| i)
+            .collect();
+
+        let index = match ready
+            .choose_weighted(&mut thread_rng(), |&i| SCHEDULED_EVENTS.events[i].weight)
+        {
+            Ok(&index) => index,
+            Err(_) => {
+                self.countdown = Self::RETRY;
+                return;
+            }
+        };
+
+        let event = &SCHEDULED_EVENTS.events[index];
+        if let Some((position, group)) = Self::start_event(world, event) {
+            world.announcements.push((
+                event
+                    .announcement
+                    .replace("{direction}", compass_direction(position)),
+                Vec::new(),
+            ));
+            self.cooldowns[index] = event.cooldown;
+            self.active = Some(ActiveEvent {
+                index,
+                remaining: event.duration,
+                position,
+                group,
+            });
+        } else {
+            warn!("could not start {:?} event", event.kind);
+            self.countdown = Self::RETRY;
+        }
+    }
+
+    /// start_event starts an event, returning its position, and group (if any), if successful.
+    fn start_event(
+        world: &mut World,
+        event: &ScheduledEvent,
+    ) -> Option<(Vec2, Option<NpcGroupId>)> {
+        let mut rng = thread_rng();
+        let position = match event.kind {
+            EventKind::TreasureFleet => {
+                let mut npcs = std::mem::take(&mut world.npcs);
+                let crossing = npcs.spawn_crossing(world, Faction::TreasureFleet);
+                world.npcs = npcs;
+                return crossing.map(|(group, position)| (position, Some(group)));
+            }
+            EventKind::SupplyDrop => {
+                // Crates can't be collected on land.
+                (0..10)
+                    .map(|_| gen_radius(&mut rng, world.radius * 0.7))
+                    .find(|&position| {
+                        world
+                            .terrain
+                            .sample(position)
+                            .map_or(true, |altitude| altitude.is_submerged())
+                    })
+            }
+            EventKind::Storm => {
+                // Crosses the world over the course of the event.
+                let edge = rng.gen::<Angle>().to_vec() * world.radius;
+                world.storm = Some(Storm {
+                    position: edge,
+                    velocity: edge * (-2.0 / event.duration.max(1) as f32),
+                    radius: Storm::RADIUS,
+                });
+                Some(edge)
+            }
+        };
+        position.map(|position| (position, None))
+    }
+
+    /// continue_event applies the effects of an active event, once per second.
+    fn continue_event(world: &mut World, kind: EventKind, position: Vec2) {
+        match kind {
+            EventKind::TreasureFleet => {}
+            EventKind::SupplyDrop => {
+                let mut rng = thread_rng();
+                for _ in 0..Self::SUPPLY_DROP_RATE {
+                    world.spawn_static(
+                        EntityType::Crate,
+                        position + gen_radius(&mut rng, Self::SUPPLY_DROP_RADIUS),
+                        rng.gen(),
+                        Velocity::ZERO,
+                        Ticks::ZERO,
+                    );
+                }
+            }
+            EventKind::Storm => {
+                if let Some(storm) = &mut world.storm {
+                    storm.position += storm.velocity;
+                }
+            }
+        }
+    }
+
+    /// end_event cleans up after an event, returning false if it was cut short.
+    fn end_event(world: &mut World, kind: EventKind, group: Option<NpcGroupId>) -> bool {
+        match kind {
+            EventKind::TreasureFleet => {
+                // Escorts may outlive the merchant, but the fleet only escaped if it did.
+                let escaped = group.map_or(false, |group| world.npcs.leader_alive(group));
+                world.npcs.despawn_faction(Faction::TreasureFleet);
+                escaped
+            }
+            EventKind::SupplyDrop => true,
+            EventKind::Storm => world.storm.take().is_some(),
+        }
+    }
+}
+
+/// weekday returns the UTC day of the week, where 0 is Sunday.
+fn weekday(time: SystemTime) -> u8 {
+    let days = time
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs() / (24 * 60 * 60));
+    // The epoch was a Thursday.
+    ((days + 4) % 7) as u8
+}
+
+/// compass_direction returns the compass direction of a position from the center of the world.
+fn compass_direction(position: Vec2) -> &'static str {
+    const DIRECTIONS: [&str; 8] = [
+        "east",
+        "north-east",
+        "north",
+        "north-west",
+        "west",
+        "south-west",
+        "south",
+        "south-east",
+    ];
+    let degrees = Angle::from(position).to_degrees();
+    DIRECTIONS[((degrees + 360.0 + 22.5) / 45.0) as usize % DIRECTIONS.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::event_scheduler::{compass_direction, weekday, SCHEDULED_EVENTS};
+    use glam::Vec2;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn scheduled_events() {
+        assert!(SCHEDULED_EVENTS.interval > 0);
+        assert!(!SCHEDULED_EVENTS.events.is_empty());
+        for event in &SCHEDULED_EVENTS.events {
+            assert!(event.weight >= 0.0);
+            assert!(event.duration > 0);
+            assert!(event.days.iter().all(|&day| day < 7));
+            assert!(!event.announcement.is_empty());
+        }
+    }
+
+    #[test]
+    fn weekday_and_direction() {
+        // Thursday, Saturday, and Sunday.
+        assert_eq!(weekday(UNIX_EPOCH), 4);
+        assert_eq!(
+            weekday(UNIX_EPOCH + Duration::from_secs(2 * 86400 + 3600)),
+            6
+        );
+        assert_eq!(weekday(UNIX_EPOCH + Duration::from_secs(3 * 86400)), 0);
+
+        assert_eq!(compass_direction(Vec2::new(0.0, 100.0)), "north");
+        assert_eq!(compass_direction(Vec2::new(100.0, -100.0)), "south-east");
+        assert_eq!(compass_direction(Vec2::new(-100.0, 1.0)), "west");
+    }
+}
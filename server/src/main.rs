@@ -18,17 +18,23 @@ use common::entity::EntityType;
 
 mod arena;
 mod bot;
+mod bot_memory;
+mod bot_tournament;
 mod collision;
 mod complete_ref;
 mod contact_ref;
 mod entities;
 mod entity;
 mod entity_extension;
+mod event_scheduler;
 mod noise;
+mod npc;
 mod player;
 mod protocol;
 mod server;
 mod world;
+mod world_bounty;
+mod world_harbor;
 mod world_inbound;
 mod world_mutation;
 mod world_outbound;
@@ -47,5 +53,11 @@ fn main() {
         }
     }
 
+    let mut args = std::env::args().skip(1);
+    if args.next().as_deref() == Some("tune-bots") {
+        bot_tournament::tune_bots(bot_tournament::TournamentOptions::from_args(args));
+        return;
+    }
+
     game_server::entry_point::entry_point::<Server>();
 }
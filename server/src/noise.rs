@@ -1,8 +1,10 @@
 // SPDX-FileCopyrightText: 2021 Softbear, Inc.
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
+use common::current::Currents;
 use common::terrain;
 use common::terrain::*;
+use glam::Vec2;
 use noise::{NoiseFn, SuperSimplex};
 use std::mem::MaybeUninit;
 
@@ -54,6 +56,32 @@ pub fn noise_generator(x: usize, y: usize) -> u8 {
     (height * 255.0) as u8
 }
 
+/// current_generator returns the ocean current velocity (in meters per second) at a given position
+/// and time (in seconds). Currents swirl around eddies, which they also slowly converge on.
+pub fn current_generator(position: Vec2, seconds: f64) -> Vec2 {
+    // Wavelength of a few kilometers, changing over the course of several minutes.
+    const S: f64 = 0.0004;
+    const T: f64 = 1.0 / 600.0;
+    const EPSILON: f64 = 0.01;
+    // Fraction of the current that converges (rather than circulates).
+    const CONVERGENCE: f32 = 0.25;
+
+    let x = position.x as f64 * S - unsafe { SEED };
+    let y = position.y as f64 * S;
+    let t = seconds * T;
+    let potential = |x: f64, y: f64| fractal_noise_3d(get_noise(), x, y, t, 2);
+
+    let center = potential(x, y);
+    let gradient = Vec2::new(
+        ((potential(x + EPSILON, y) - center) / EPSILON) as f32,
+        ((potential(x, y + EPSILON) - center) / EPSILON) as f32,
+    );
+
+    // Curl is divergence free, so currents circulate, but the gradient makes them converge.
+    let velocity = gradient.perp() * (1.0 - CONVERGENCE) - gradient * CONVERGENCE;
+    velocity.clamp_length_max(Currents::MAX_SPEED)
+}
+
 /// fractal noise returns multi-level noise for a given fractional coordinate.
 #[inline]
 fn fractal_noise(noise: &SuperSimplex, x: f64, y: f64, octaves: u32) -> f64 {
@@ -65,6 +93,17 @@ fn fractal_noise(noise: &SuperSimplex, x: f64, y: f64, octaves: u32) -> f64 {
         .sum()
 }
 
+/// fractal noise 3d is like fractal noise, but with a third (unscaled) coordinate, such as time.
+#[inline]
+fn fractal_noise_3d(noise: &SuperSimplex, x: f64, y: f64, z: f64, octaves: u32) -> f64 {
+    (0..octaves)
+        .map(|i| {
+            let freq = (1 << i) as f64;
+            noise.get([x * freq, y * freq, z]) * (1.0 / freq)
+        })
+        .sum()
+}
+
 #[cfg(test)]
 mod tests {
     use super::init;
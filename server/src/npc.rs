@@ -0,0 +1,430 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::bot::{Bot, BotParameters};
+use crate::entity::Entity;
+use crate::player::{Player, Status};
+use crate::protocol::AsCommandTrait;
+use crate::server::Server;
+use crate::world::World;
+use common::angle::Angle;
+use common::entity::*;
+use common::guidance::Guidance;
+use common::protocol::Command;
+use common::util::{gen_radius, level_to_score};
+use core_protocol::id::PlayerId;
+use game_server::context::{PlayerData, PlayerTuple};
+use glam::Vec2;
+use log::warn;
+use rand::seq::IteratorRandom;
+use rand::{thread_rng, Rng};
+use std::sync::Arc;
+
+/// Faction is a kind of world-owned group of boats, friendly to others of the same faction.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Faction {
+    /// Roaming raiders that hunt anything nearby.
+    Pirate,
+    /// A merchant carrying loot, and the escorts that protect it.
+    Convoy,
+    /// A heavily laden, heavily escorted convoy that only appears during scheduled events.
+    TreasureFleet,
+}
+
+impl Faction {
+    /// Target density of groups of this faction (per square meter).
+    fn density(self) -> f32 {
+        match self {
+            Self::Pirate => 1.0 / 15000000.0,
+            Self::Convoy => 1.0 / 25000000.0,
+            // Spawned by the event scheduler instead.
+            Self::TreasureFleet => 0.0,
+        }
+    }
+
+    /// group returns the entity types of a new group, leader first.
+    fn group(self) -> Vec<EntityType> {
+        match self {
+            Self::Pirate => {
+                let leader = choose_boat(|d| d.sub_kind == EntitySubKind::Pirate);
+                let raider = choose_boat(|d| d.sub_kind == EntitySubKind::Mtb && d.level <= 2);
+                leader
+                    .into_iter()
+                    .chain(raider)
+                    .chain(raider.filter(|_| thread_rng().gen_bool(0.5)))
+                    .collect()
+            }
+            Self::Convoy | Self::TreasureFleet => {
+                let (max_escort_level, escorts) = if self == Self::TreasureFleet {
+                    (6, 4)
+                } else {
+                    (4, 2)
+                };
+                let escort = || {
+                    choose_boat(|d| {
+                        matches!(
+                            d.sub_kind,
+                            EntitySubKind::Corvette | EntitySubKind::Destroyer
+                        ) && d.level <= max_escort_level
+                    })
+                };
+                choose_boat(|d| d.sub_kind == EntitySubKind::Tanker)
+                    .map(|merchant| {
+                        std::iter::once(merchant)
+                            .chain((0..escorts).filter_map(|_| escort()))
+                            .collect()
+                    })
+                    .unwrap_or_default()
+            }
+        }
+    }
+
+    /// parameters returns the personality of members of this faction.
+    fn parameters(self) -> BotParameters {
+        match self {
+            Self::Pirate => BotParameters {
+                max_aggression: 0.3,
+                // Pirates close in on enemies instead of avoiding them.
+                enemy_repulsion: -0.5,
+                ..BotParameters::default()
+            },
+            Self::Convoy | Self::TreasureFleet => BotParameters {
+                max_aggression: 0.15,
+                collectible_attraction: 0.0,
+                ..BotParameters::default()
+            },
+        }
+    }
+
+    /// score returns the score of a member, which determines how much loot it drops and how much
+    /// its killer is rewarded.
+    fn score(self, entity_type: EntityType, leader: bool) -> u32 {
+        let level = entity_type.data().level;
+        match self {
+            // Merchants are laden with cargo.
+            Self::Convoy if leader => level_to_score(level + 2),
+            Self::TreasureFleet if leader => level_to_score(level + 5),
+            _ => level_to_score(level),
+        }
+    }
+}
+
+/// choose_boat returns a random boat type whose data passes the filter.
+fn choose_boat(filter: impl Fn(&EntityData) -> bool) -> Option<EntityType> {
+    EntityType::iter()
+        .filter(|t| {
+            let data = t.data();
+            data.kind == EntityKind::Boat && filter(data)
+        })
+        .choose(&mut thread_rng())
+}
+
+/// A world-owned boat, controlled by server-side AI.
+struct Npc {
+    bot: Bot,
+    /// Never shared with the core, so non-player characters do not occupy player slots or appear
+    /// on the liveboard. Only exists to own the boat's extension and receive kill credit.
+    player_tuple: Arc<PlayerTuple<Server>>,
+}
+
+impl Npc {
+    fn entity<'a>(&self, world: &'a World) -> Option<&'a Entity> {
+        match self.player_tuple.borrow_player().data.status {
+            Status::Alive { entity_index, .. } => Some(&world.entities[entity_index]),
+            _ => None,
+        }
+    }
+}
+
+/// Identifies a group for its whole life, unlike the player ids of its members, which are
+/// reused once they sink.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct NpcGroupId(u32);
+
+/// A pirate band or a convoy.
+struct NpcGroup {
+    id: NpcGroupId,
+    faction: Faction,
+    members: Vec<Npc>,
+    /// Whether the boat the group was spawned around has sunk, after which an escort leads.
+    leader_sunk: bool,
+    /// Where the group is currently headed.
+    destination: Vec2,
+}
+
+/// Npcs manages world-owned non-player characters.
+#[derive(Default)]
+pub struct Npcs {
+    groups: Vec<NpcGroup>,
+    /// Next candidate for PlayerId::nth_npc.
+    next_id: usize,
+    /// Next NpcGroupId.
+    next_group_id: u32,
+    /// Total visual area of non-player boats, so they don't grow the world.
+    pub visual_area: f32,
+}
+
+impl Npcs {
+    const FACTIONS: [Faction; 2] = [Faction::Pirate, Faction::Convoy];
+
+    /// update spawns, despawns, and controls non-player characters.
+    pub fn update(&mut self, world: &mut World) {
+        // Forget sunk boats and groups.
+        for group in &mut self.groups {
+            let is_alive = |npc: &Npc| npc.player_tuple.borrow_player().data.status.is_alive();
+            if !group.leader_sunk && group.members.first().map_or(false, |npc| !is_alive(npc)) {
+                group.leader_sunk = true;
+            }
+            group.members.retain(is_alive);
+        }
+        self.groups.retain(|group| !group.members.is_empty());
+
+        for faction in Self::FACTIONS {
+            let count = self.groups.iter().filter(|g| g.faction == faction).count();
+            let target = world.target_count(faction.density());
+
+            if count < target {
+                // At most one group per update, to spread out the cost.
+                let mut rng = thread_rng();
+                let position = gen_radius(&mut rng, world.radius * 0.8);
+                let destination = gen_radius(&mut rng, world.radius * 0.7);
+                self.spawn_group(world, faction, position, destination);
+            } else if count > target + 1 {
+                self.despawn_group(faction);
+            }
+        }
+
+        for group in &mut self.groups {
+            Self::control_group(world, group);
+        }
+
+        self.visual_area = self
+            .groups
+            .iter()
+            .flat_map(|g| g.members.iter())
+            .filter_map(|npc| npc.entity(world))
+            .map(|e| e.data().visual_area())
+            .sum();
+    }
+
+    /// next_player_id allocates a player id that isn't used by any current non-player character.
+    fn next_player_id(&mut self) -> Option<PlayerId> {
+        for _ in 0..PlayerId::NPC_COUNT {
+            let n = self.next_id;
+            self.next_id = (self.next_id + 1) % PlayerId::NPC_COUNT;
+
+            let player_id = PlayerId::nth_npc(n)?;
+            if !self
+                .groups
+                .iter()
+                .flat_map(|g| g.members.iter())
+                .any(|npc| npc.player_tuple.borrow_player().player_id == player_id)
+            {
+                return Some(player_id);
+            }
+        }
+        None
+    }
+
+    /// spawn_crossing spawns a group of the faction near the edge of the world, headed for the
+    /// opposite edge, regardless of the faction's density. Returns the id of the group and the
+    /// position of its leader, if it could be spawned.
+    pub fn spawn_crossing(
+        &mut self,
+        world: &mut World,
+        faction: Faction,
+    ) -> Option<(NpcGroupId, Vec2)> {
+        let edge = thread_rng().gen::<Angle>().to_vec() * (world.radius * 0.8);
+        if !self.spawn_group(world, faction, edge, -edge) {
+            return None;
+        }
+        let group = self.groups.last()?;
+        let position = group.members.first()?.entity(world)?.transform.position;
+        Some((group.id, position))
+    }
+
+    /// group_exists returns whether any member of the group is still afloat.
+    pub fn group_exists(&self, id: NpcGroupId) -> bool {
+        self.groups.iter().any(|g| g.id == id)
+    }
+
+    /// leader_alive returns whether the boat the group was spawned around is still afloat.
+    pub fn leader_alive(&self, id: NpcGroupId) -> bool {
+        self.groups.iter().any(|g| {
+            g.id == id
+                && !g.leader_sunk
+                && g.members.first().map_or(false, |npc| {
+                    npc.player_tuple.borrow_player().data.status.is_alive()
+                })
+        })
+    }
+
+    /// despawn_faction removes all groups of the faction, without dropping loot. Returns whether
+    /// any were removed.
+    pub fn despawn_faction(&mut self, faction: Faction) -> bool {
+        let mut any = false;
+        while self.groups.iter().any(|g| g.faction == faction) {
+            self.despawn_group(faction);
+            any = true;
+        }
+        any
+    }
+
+    /// spawn_group spawns a group of the faction near position, headed for destination. Returns
+    /// whether it was spawned.
+    fn spawn_group(
+        &mut self,
+        world: &mut World,
+        faction: Faction,
+        mut position: Vec2,
+        destination: Vec2,
+    ) -> bool {
+        let mut group = NpcGroup {
+            id: NpcGroupId(self.next_group_id),
+            faction,
+            members: Vec::new(),
+            leader_sunk: false,
+            destination,
+        };
+
+        let mut spawn_radius = world.radius * 0.2;
+
+        for (i, entity_type) in faction.group().into_iter().enumerate() {
+            let player_id = match self.next_player_id() {
+                Some(player_id) => player_id,
+                None => break,
+            };
+
+            let mut player = PlayerData::new(player_id, None);
+            player.score = faction.score(entity_type, i == 0);
+            player.data = Player {
+                faction: Some(faction),
+                ..Player::default()
+            };
+
+            let npc = Npc {
+                bot: Bot::new(faction.parameters()),
+                player_tuple: Arc::new(PlayerTuple::new(player)),
+            };
+
+            let mut boat = Entity::new(entity_type, Some(Arc::clone(&npc.player_tuple)));
+            boat.transform.position = position;
+            if !world.spawn_here_or_nearby(boat, spawn_radius, None) {
+                if i == 0 {
+                    // Group is pointless without its leader.
+                    return false;
+                }
+                continue;
+            }
+
+            if i == 0 {
+                // Escorts spawn near the leader.
+                if let Some(leader) = npc.entity(world) {
+                    position = leader.transform.position;
+                    spawn_radius = leader.data().radius + 100.0;
+                }
+            }
+
+            group.members.push(npc);
+        }
+
+        self.next_group_id = self.next_group_id.wrapping_add(1);
+        self.groups.push(group);
+        true
+    }
+
+    /// despawn_group removes a group of the faction, without dropping loot.
+    fn despawn_group(&mut self, faction: Faction) {
+        if let Some(i) = self.groups.iter().rposition(|g| g.faction == faction) {
+            for npc in self.groups.swap_remove(i).members {
+                // Entities are removed during the next physics tick.
+                npc.player_tuple.borrow_player_mut().data.flags.left_game = true;
+            }
+        }
+    }
+
+    fn control_group(world: &mut World, group: &mut NpcGroup) {
+        let leader_position = group
+            .members
+            .first()
+            .and_then(|npc| npc.entity(world))
+            .map(|e| e.transform.position);
+
+        if let Some(leader_position) = leader_position {
+            if leader_position.distance_squared(group.destination) < 200f32.powi(2) {
+                group.destination = gen_radius(&mut thread_rng(), world.radius * 0.7);
+            }
+        }
+
+        for (i, npc) in group.members.iter_mut().enumerate() {
+            let player_id = npc.player_tuple.borrow_player().player_id;
+            let update = world.get_player_complete(&npc.player_tuple);
+            let command = npc.bot.update(update, player_id);
+
+            // Non-player characters never respawn or upgrade.
+            let mut control = match command {
+                Some(Command::Control(control)) => control,
+                _ => continue,
+            };
+
+            if let Some(entity) = npc.entity(world) {
+                let data = entity.data();
+                let position = entity.transform.position;
+
+                // The leader follows the route, and escorts follow the leader.
+                let target = if i == 0 {
+                    Some(group.destination)
+                } else if group.faction != Faction::Pirate {
+                    leader_position.filter(|p| p.distance_squared(position) > 250f32.powi(2))
+                } else {
+                    None
+                };
+
+                if let Some(target) = target {
+                    let fighting = control.fire.is_some() && group.faction == Faction::Pirate;
+                    if !fighting {
+                        control.guidance = Some(Guidance {
+                            direction_target: Angle::from(target - position),
+                            velocity_target: data.speed
+                                * if group.faction != Faction::Pirate {
+                                    0.5
+                                } else {
+                                    0.7
+                                },
+                        });
+                    }
+                }
+            }
+
+            if let Err(e) = Command::Control(control)
+                .as_command()
+                .apply(world, &npc.player_tuple)
+            {
+                warn!("NPC command resulted in {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::npc::{Faction, Npcs};
+    use common::entity::{EntityKind, EntityType};
+
+    #[test]
+    fn groups() {
+        unsafe {
+            EntityType::init();
+        }
+
+        for faction in [Faction::Pirate, Faction::Convoy, Faction::TreasureFleet] {
+            for _ in 0..10 {
+                let group = faction.group();
+                assert!(!group.is_empty());
+                for entity_type in group {
+                    assert_eq!(entity_type.data().kind, EntityKind::Boat);
+                }
+            }
+        }
+    }
+}
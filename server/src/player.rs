@@ -2,6 +2,8 @@
 // SPDX-License-Identifier: AGPL-3.0-or-later
 
 use crate::entities::*;
+use crate::npc::Faction;
+use common::consumable::Consumable;
 use common::death_reason::DeathReason;
 use common::protocol::Hint;
 use glam::Vec2;
@@ -99,6 +101,14 @@ pub struct Player {
     pub hint: Hint,
     /// Current status e.g. Alive, Dead, or Spawning.
     pub status: Status,
+    /// Only non-player characters have a faction, and are friendly to others of the same faction.
+    pub faction: Option<Faction>,
+    /// Boats sunk since spawning.
+    pub kill_streak: u32,
+    /// Extra score for whoever sinks the player's boat.
+    pub bounty: u32,
+    /// Consumables bought since spawning.
+    pub consumables: Vec<Consumable>,
 }
 
 impl Default for Player {
@@ -108,6 +118,10 @@ impl Default for Player {
             flags: Flags::default(),
             hint: Hint::default(),
             status: Status::Spawning,
+            faction: None,
+            kill_streak: 0,
+            bounty: 0,
+            consumables: Vec::new(),
         }
     }
 }
@@ -5,6 +5,7 @@ use crate::bot::*;
 use crate::complete_ref::CompleteRef;
 use crate::contact_ref::ContactRef;
 use crate::entity_extension::EntityExtension;
+use crate::event_scheduler::EventScheduler;
 use crate::player::*;
 use crate::protocol::*;
 use crate::world::World;
@@ -28,6 +29,8 @@ use std::time::Duration;
 /// A game server.
 pub struct Server {
     pub world: World,
+    /// Triggers world events, such as treasure fleets and storms.
+    pub scheduler: EventScheduler,
 }
 
 /// Stores a player, and metadata related to it. Data stored here may only be accessed when processing,
@@ -64,6 +67,7 @@ impl GameArenaService for Server {
             world: World::new(World::target_radius(
                 min_players as f32 * EntityType::FairmileD.data().visual_area(),
             )),
+            scheduler: EventScheduler::default(),
         }
     }
 
@@ -154,6 +158,7 @@ impl GameArenaService for Server {
         player_entity.map(|e| CoreStatus {
             location: e.transform.position.extend(0.0),
             score: e.borrow_player().score,
+            bounty: e.borrow_player().data.bounty,
         })
     }
 
@@ -163,6 +168,11 @@ impl GameArenaService for Server {
 
         self.world.update(ticks);
 
+        if counter % Ticks::FREQUENCY_HZ == Ticks::ZERO {
+            self.world.update_bounties();
+            self.scheduler.update(&mut self.world);
+        }
+
         // Needs to be called before clients receive updates, but after World::update.
         self.world.terrain.pre_update();
 
@@ -180,5 +190,10 @@ impl GameArenaService for Server {
     fn post_update(&mut self) {
         // Needs to be after clients receive updates.
         self.world.terrain.post_update();
+        self.world.events.clear();
+    }
+
+    fn take_announcements(&mut self) -> Vec<(String, Vec<PlayerId>)> {
+        std::mem::take(&mut self.world.announcements)
     }
 }
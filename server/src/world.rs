@@ -4,11 +4,17 @@
 use crate::arena::Arena;
 use crate::entities::{Entities, EntityIndex};
 use crate::entity::Entity;
-use crate::noise::noise_generator;
+use crate::noise::{current_generator, noise_generator};
+use crate::npc::Npcs;
 use common::death_reason::DeathReason;
 use common::entity::{EntityKind, EntityType};
+use common::harbor::Harbor;
+use common::protocol::{AntiAircraftFire, Event};
+use common::storm::Storm;
 use common::terrain::Terrain;
 use common::ticks::Ticks;
+use core_protocol::id::PlayerId;
+use glam::Vec2;
 
 /// A game world of variable radius, consisting of entities and a terrain.
 pub struct World {
@@ -16,6 +22,25 @@ pub struct World {
     pub entities: Entities,
     pub terrain: Terrain,
     pub radius: f32,
+    /// Non-player characters, which belong to the world rather than to any client or bot.
+    pub npcs: Npcs,
+    /// Anti-aircraft gunfire during the last tick, and where it came from.
+    pub anti_aircraft_fire: Vec<(Vec2, AntiAircraftFire)>,
+    /// Events since clients last received updates, and which player they concern. Cleared after
+    /// clients receive updates, so that events from commands aren't lost.
+    pub events: Vec<(PlayerId, Event)>,
+    /// Messages to announce via chat, in which each "{n}" is replaced by the alias of the n-th
+    /// player.
+    pub announcements: Vec<(String, Vec<PlayerId>)>,
+    /// Time (in seconds) that ocean currents vary with. Double precision, so that currents keep
+    /// varying after weeks of uptime.
+    pub current_seconds: f64,
+    /// Sheltered bays in which boats repair and reload, but cannot use weapons.
+    pub harbors: Vec<Harbor>,
+    /// Radius within which harbors have already been placed.
+    pub harbor_radius: f32,
+    /// Storm front caused by a scheduled event, if any.
+    pub storm: Option<Storm>,
 }
 
 impl World {
@@ -26,11 +51,22 @@ impl World {
             entities: Entities::new(),
             terrain: Terrain::with_generator(noise_generator),
             radius: initial_radius,
+            npcs: Npcs::default(),
+            anti_aircraft_fire: Vec::new(),
+            events: Vec::new(),
+            announcements: Vec::new(),
+            current_seconds: 0.0,
+            harbors: Vec::new(),
+            harbor_radius: 0.0,
+            storm: None,
         }
     }
 
     /// update updates the internals of the world, spawning and updating existing entities.
     pub fn update(&mut self, delta: Ticks) {
+        self.current_seconds += delta.to_secs() as f64;
+        self.place_harbors();
+        self.update_npcs();
         self.spawn_statics(delta);
         self.physics(delta);
         self.physics_radius(delta);
@@ -45,7 +81,8 @@ impl World {
                     0.0
                 }
             })
-            .sum::<f32>();
+            .sum::<f32>()
+            - self.npcs.visual_area;
 
         let target_radius = Self::target_radius(total_visual_area);
         let s = delta.to_secs();
@@ -58,6 +95,18 @@ impl World {
         }
     }
 
+    /// current_at returns the velocity of the ocean current at a position.
+    pub fn current_at(&self, position: Vec2) -> Vec2 {
+        current_generator(position, self.current_seconds)
+    }
+
+    /// update_npcs controls non-player characters, which need mutable access to the world.
+    fn update_npcs(&mut self) {
+        let mut npcs = std::mem::take(&mut self.npcs);
+        npcs.update(self);
+        self.npcs = npcs;
+    }
+
     /// add adds an entity to the world (assigning it an id).
     pub fn add(&mut self, mut entity: Entity) {
         entity.id = self.arena.new_id(entity.entity_type);
@@ -0,0 +1,51 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::world::World;
+use common::util::{bounty_growth, level_to_score, max_bounty};
+use rayon::prelude::*;
+use std::cmp::Reverse;
+use std::sync::Arc;
+
+impl World {
+    /// How many of the highest scoring players are wanted, regardless of kill streak.
+    const BOUNTY_RANKS: usize = 3;
+    /// Minimum score to be wanted for being near the top of the liveboard, so that a small arena
+    /// doesn't put bounties on beginners.
+    const BOUNTY_MIN_SCORE: u32 = level_to_score(6);
+    /// Minimum kill streak to be wanted, regardless of score.
+    const BOUNTY_KILL_STREAK: u32 = 3;
+
+    /// update_bounties places bounties on, and grows the bounties of, players who are near the top
+    /// of the liveboard or on a kill streak. Should be called once per second.
+    pub fn update_bounties(&mut self) {
+        let mut players: Vec<_> = self
+            .entities
+            .par_iter()
+            .filter(|(_, entity)| entity.is_boat() && !entity.borrow_player().player_id.is_npc())
+            .map(|(_, entity)| Arc::clone(entity.player.as_ref().unwrap()))
+            .collect();
+
+        players.sort_unstable_by_key(|player| Reverse(player.borrow_player().score));
+
+        for (rank, player) in players.iter().enumerate() {
+            let mut player = player.borrow_player_mut();
+            let wanted = (rank < Self::BOUNTY_RANKS && player.score >= Self::BOUNTY_MIN_SCORE)
+                || player.data.kill_streak >= Self::BOUNTY_KILL_STREAK;
+
+            if !wanted {
+                continue;
+            }
+
+            if player.data.bounty == 0 {
+                self.announcements.push((
+                    String::from("A bounty has been placed on {0}!"),
+                    vec![player.player_id],
+                ));
+            }
+
+            let bounty = player.data.bounty + bounty_growth(player.score, player.data.kill_streak);
+            player.data.bounty = bounty.min(max_bounty(player.score));
+        }
+    }
+}
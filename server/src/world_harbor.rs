@@ -0,0 +1,164 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use crate::world::World;
+use common::altitude::Altitude;
+use common::angle::Angle;
+use common::harbor::Harbor;
+use common::terrain::{Terrain, SCALE};
+use common::world::ARCTIC;
+use glam::Vec2;
+use rayon::prelude::*;
+use server_util::benchmark::Timer;
+use server_util::benchmark_scope;
+use std::cmp::Ordering;
+
+impl World {
+    /// How much faster boats repair in a harbor.
+    pub const HARBOR_REPAIR_FACTOR: f32 = 5.0;
+    /// How much faster boats reload in a harbor.
+    pub const HARBOR_RELOAD_FACTOR: f32 = 3.0;
+    /// Distance between candidate harbor positions.
+    const HARBOR_CANDIDATE_SPACING: f32 = 200.0;
+    /// Minimum distance between harbors.
+    const HARBOR_SPACING: f32 = 3000.0;
+    /// How far ahead of the world border harbors are placed, so they exist before the area
+    /// becomes reachable.
+    const HARBOR_LOOKAHEAD: f32 = 1000.0;
+    /// How far to look for land that shelters a bay.
+    const SHELTER_DISTANCE: f32 = 700.0;
+    /// How many directions to look for land in.
+    const SHELTER_DIRECTIONS: usize = 16;
+    /// Minimum fraction of directions that must be sheltered by land. The remaining directions
+    /// are the harbor's mouth, which must exist for boats to enter.
+    const MIN_SHELTER: f32 = 0.6;
+
+    /// place_harbors places harbors in sheltered bays, ahead of the growing world border. Each part
+    /// of the world is only considered once, so harbors never move.
+    pub fn place_harbors(&mut self) {
+        let max_radius = Terrain::max_world_radius();
+        if self.harbor_radius >= max_radius
+            || self.radius + Self::HARBOR_LOOKAHEAD * 0.5 < self.harbor_radius
+        {
+            // Still far enough ahead of the world border.
+            return;
+        }
+        let radius = (self.radius + Self::HARBOR_LOOKAHEAD).min(max_radius);
+
+        benchmark_scope!("place_harbors");
+
+        let inner_radius = self.harbor_radius;
+        let steps = (radius / Self::HARBOR_CANDIDATE_SPACING).ceil() as i32;
+        let terrain = &self.terrain;
+
+        let mut candidates: Vec<(Vec2, f32)> = (-steps..=steps)
+            .into_par_iter()
+            .flat_map_iter(|y| (-steps..=steps).map(move |x| (x, y)))
+            .filter_map(|(x, y)| {
+                let position = Vec2::new(x as f32, y as f32) * Self::HARBOR_CANDIDATE_SPACING;
+                let distance_squared = position.length_squared();
+                if distance_squared <= inner_radius.powi(2) || distance_squared > radius.powi(2) {
+                    // Already considered, or not yet.
+                    return None;
+                }
+                if position.y > ARCTIC - Harbor::RADIUS {
+                    // Arctic bays are full of ice.
+                    return None;
+                }
+                Self::harbor_shelter(terrain, position).map(|shelter| (position, shelter))
+            })
+            .collect();
+
+        // Prefer the most sheltered bays.
+        candidates.sort_unstable_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap_or(Ordering::Equal));
+
+        for (position, _) in candidates {
+            if self
+                .harbors
+                .iter()
+                .all(|h| h.position.distance_squared(position) >= Self::HARBOR_SPACING.powi(2))
+            {
+                self.harbors.push(Harbor { position });
+            }
+        }
+
+        self.harbor_radius = radius;
+    }
+
+    /// harbor_shelter returns the fraction of directions in which a position is sheltered by land,
+    /// or None if the position isn't a suitable harbor (not enough open water, or no mouth).
+    fn harbor_shelter(terrain: &Terrain, position: Vec2) -> Option<f32> {
+        let is_land = |position: Vec2| {
+            terrain
+                .sample(position)
+                .map(|altitude| altitude >= Altitude::ZERO)
+                .unwrap_or(true)
+        };
+
+        // Most of the harbor should be open water.
+        let clearance = Harbor::RADIUS * 0.5;
+        let step = SCALE * 2.0;
+
+        let mut sheltered = 0;
+        for i in 0..Self::SHELTER_DIRECTIONS {
+            let direction = Angle::from_radians(
+                i as f32 * (std::f32::consts::TAU / Self::SHELTER_DIRECTIONS as f32),
+            )
+            .to_vec();
+
+            let mut distance = 0.0;
+            while distance <= Self::SHELTER_DISTANCE {
+                if is_land(position + direction * distance) {
+                    if distance < clearance {
+                        return None;
+                    }
+                    sheltered += 1;
+                    break;
+                }
+                distance += step;
+            }
+        }
+
+        let shelter = sheltered as f32 / Self::SHELTER_DIRECTIONS as f32;
+        (shelter >= Self::MIN_SHELTER && sheltered < Self::SHELTER_DIRECTIONS).then(|| shelter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::world::World;
+    use common::terrain::{Terrain, SIZE};
+    use glam::Vec2;
+
+    /// Generates a ring of land around the center of the terrain, optionally with a mouth on the
+    /// south side.
+    fn ring(x: usize, y: usize, mouth: bool) -> u8 {
+        let center = (SIZE / 2) as f32;
+        let delta = Vec2::new(x as f32 - center, y as f32 - center);
+        let distance = delta.length();
+        if (16.0..=30.0).contains(&distance) && (!mouth || delta.y >= -12.0) {
+            255
+        } else {
+            0
+        }
+    }
+
+    #[test]
+    fn harbor_shelter() {
+        let bay = Terrain::with_generator(|x, y| ring(x, y, true));
+        let shelter = World::harbor_shelter(&bay, Vec2::ZERO).unwrap();
+        assert!(
+            shelter >= World::MIN_SHELTER && shelter < 1.0,
+            "{}",
+            shelter
+        );
+
+        // No mouth.
+        let lake = Terrain::with_generator(|x, y| ring(x, y, false));
+        assert_eq!(World::harbor_shelter(&lake, Vec2::ZERO), None);
+
+        // No shelter.
+        let ocean = Terrain::new();
+        assert_eq!(World::harbor_shelter(&ocean, Vec2::ZERO), None);
+    }
+}
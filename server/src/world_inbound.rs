@@ -37,6 +37,10 @@ impl CommandTrait for Spawn {
             return Err("cannot spawn as given entity type");
         }
 
+        if !self.entity_type.data().is_valid_loadout(self.loadout) {
+            return Err("invalid loadout");
+        }
+
         /*
         // Default to spawning near the center of the world, with more points making you spawn further north.
         let vertical_bias = map_ranges(
@@ -144,6 +148,7 @@ impl CommandTrait for Spawn {
 
         let mut boat = Entity::new(self.entity_type, Some(Arc::clone(player_tuple)));
         boat.transform.position = spawn_position;
+        boat.extension_mut().loadout = self.loadout;
         if world.spawn_here_or_nearby(boat, spawn_radius, exclusion_zone) {
             Ok(())
         } else {
@@ -233,10 +238,19 @@ impl CommandTrait for Fire {
 
             let entity = &mut world.entities[entity_index];
 
+            if world
+                .harbors
+                .iter()
+                .any(|harbor| harbor.contains(entity.transform.position))
+            {
+                return Err("cannot fire in harbor");
+            }
+
             let data = entity.data();
+            let armaments = entity.armaments();
 
             let index = self.armament_index as usize;
-            if index >= data.armaments.len() {
+            if index >= armaments.len() {
                 return Err("armament index out of bounds");
             }
 
@@ -244,7 +258,11 @@ impl CommandTrait for Fire {
                 return Err("armament not yet reloaded");
             }
 
-            let armament = &data.armaments[index];
+            if entity.extension().subsystems.is_armament_disabled(index) {
+                return Err("armament disabled by damage");
+            }
+
+            let armament = &armaments[index];
             let armament_entity_data = armament.entity_type.data();
 
             if entity.altitude.is_submerged() {
@@ -268,8 +286,12 @@ impl CommandTrait for Fire {
                 }
             }
 
-            let armament_transform =
-                entity.transform + data.armament_transform(&entity.extension().turrets, index);
+            let armament_transform = entity.transform
+                + data.armament_transform(
+                    entity.extension().loadout,
+                    &entity.extension().turrets,
+                    index,
+                );
 
             if armament_entity_data.sub_kind == EntitySubKind::Depositor {
                 if let Some(mut target) = aim_target {
@@ -325,6 +347,16 @@ impl CommandTrait for Fire {
                 };
                 armament_entity.transform.direction += thread_rng().gen::<Angle>() * deviation;
 
+                if armament_entity_data.is_ballistic() {
+                    if let Some(aim) = aim_target {
+                        // Ballistic shells land where they are aimed (if within range).
+                        let distance = aim.distance(armament_entity.transform.position);
+                        armament_entity.ticks = armament.entity_type.reduced_lifespan(
+                            Ticks::from_secs(distance / armament_entity_data.speed.to_mps()),
+                        );
+                    }
+                }
+
                 if !world.spawn_here_or_nearby(armament_entity, 0.0, None) {
                     return Err("failed to fire from current location");
                 }
@@ -420,14 +452,21 @@ impl CommandTrait for Upgrade {
                 return Err("cannot upgrade to provided entity type");
             }
 
+            if !self.entity_type.data().is_valid_loadout(self.loadout) {
+                return Err("invalid loadout");
+            }
+
             if outside_area(self.entity_type, entity.transform.position) {
                 return Err("cannot upgrade outside the correct area");
             }
 
             player.data.flags.upgraded = true;
+            world
+                .events
+                .push((player.player_id, Event::Upgraded(self.entity_type)));
             drop(player);
 
-            entity.change_entity_type(self.entity_type, &mut world.arena);
+            entity.change_entity_type(self.entity_type, self.loadout, &mut world.arena);
 
             Ok(())
         } else {
@@ -436,6 +475,50 @@ impl CommandTrait for Upgrade {
     }
 }
 
+impl CommandTrait for Buy {
+    fn apply(
+        &self,
+        world: &mut World,
+        player_tuple: &Arc<PlayerTuple<Server>>,
+    ) -> Result<(), &'static str> {
+        let mut player = player_tuple.borrow_player_mut();
+
+        if let Status::Alive { entity_index, .. } = player.data.status {
+            let data = self.consumable.data();
+            let bought = player
+                .data
+                .consumables
+                .iter()
+                .filter(|&&c| c == self.consumable)
+                .count();
+            if bought >= data.limit as usize {
+                return Err("already bought too many this life");
+            }
+
+            let entity = &mut world.entities[entity_index];
+
+            // Like paying, buying can't cost the player their level.
+            let price = data.price();
+            if player.score < level_to_score(entity.data().level) + price {
+                return Err("insufficient funds");
+            }
+
+            player.score -= price;
+            player.data.consumables.push(self.consumable);
+            world
+                .events
+                .push((player.player_id, Event::Bought(self.consumable)));
+            drop(player);
+
+            entity.consume(self.consumable);
+
+            Ok(())
+        } else {
+            Err("cannot buy while not alive")
+        }
+    }
+}
+
 /// Returns an error if the float isn't finite. Otherwise, clamps it to the provided range.
 fn sanitize_float(float: f32, valid: Range<f32>) -> Result<f32, &'static str> {
     if float.is_finite() {
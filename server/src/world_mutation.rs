@@ -5,14 +5,18 @@ use crate::entities::EntityIndex;
 use crate::entity::Entity;
 use crate::server::Server;
 use crate::world::World;
+use common::affliction::Affliction;
 use common::altitude::Altitude;
 use common::angle::Angle;
 use common::death_reason::DeathReason;
 use common::entity::*;
 use common::guidance::Guidance;
+use common::protocol::Event;
+use common::subsystems::Subsystem;
 use common::ticks::Ticks;
 use common::util::*;
 use common::velocity::Velocity;
+use core_protocol::id::PlayerId;
 use game_server::context::PlayerTuple;
 use glam::Vec2;
 use rand::{thread_rng, Rng};
@@ -23,6 +27,7 @@ use std::sync::Arc;
 pub(crate) enum Mutation {
     CollidedWithBoat {
         other_player: Arc<PlayerTuple<Server>>,
+        other_entity_type: EntityType,
         damage: Ticks,
         impulse: Velocity,
         ram: bool,
@@ -33,8 +38,9 @@ pub(crate) enum Mutation {
     },
     ClearSpawnProtection,
     UpgradeHq,
-    #[allow(dead_code)]
     Score(u32),
+    /// Advances the decay of a wreck that is being salvaged.
+    Salvage(Ticks),
     Remove(DeathReason),
     Repair(Ticks),
     Reload(Ticks),
@@ -44,7 +50,22 @@ pub(crate) enum Mutation {
     },
     // For things that may only be collected once.
     CollectedBy(Arc<PlayerTuple<Server>>, u32),
-    HitBy(Arc<PlayerTuple<Server>>, EntityType, Ticks),
+    HitBy {
+        other_player: Arc<PlayerTuple<Server>>,
+        weapon_type: EntityType,
+        damage: Ticks,
+        subsystem: Option<Subsystem>,
+    },
+    /// Sets a boat on fire, or floods it, with another stack.
+    Afflict {
+        affliction: Affliction,
+        other_player: Arc<PlayerTuple<Server>>,
+        weapon_type: EntityType,
+    },
+    /// Puts out one stack of each affliction.
+    Extinguish,
+    /// Reveals a boat, due to a nearby splash.
+    Bracket,
     Attraction(Vec2, Velocity),
     Guidance {
         direction_target: Angle,
@@ -60,7 +81,7 @@ impl Mutation {
         match self {
             Self::FireAll(_) => 127, // so that ASROC can fire before expiring
             Self::Remove(_) => 126,
-            Self::HitBy(_, _, _) => 125,
+            Self::HitBy { .. } => 125,
             Self::CollidedWithBoat { .. } => 124,
             Self::CollectedBy(_, _) => 123,
             Self::Attraction(_, _) => 101,
@@ -87,7 +108,7 @@ impl Mutation {
                 signal_strength, ..
             } => -signal_strength,
             // Highest damage goes first.
-            Self::HitBy(_, _, damage) => damage.to_secs(),
+            Self::HitBy { damage, .. } => damage.to_secs(),
             Self::CollidedWithBoat { damage, .. } => damage.to_secs(),
             // Closest attraction goes last (takes effect).
             Self::Attraction(delta, _) => delta.length_squared(),
@@ -121,16 +142,24 @@ impl Mutation {
                 world.remove(index, reason);
                 return true;
             }
-            Self::HitBy(other_player, weapon_type, damage) => {
+            Self::HitBy {
+                other_player,
+                weapon_type,
+                damage,
+                subsystem,
+            } => {
                 let e = &mut entities[index];
+                if let Some(subsystem) = subsystem {
+                    e.extension_mut().subsystems.damage(subsystem, damage);
+                }
+                e.extension_mut()
+                    .damage_contributors
+                    .add(&other_player, weapon_type, damage);
+                Self::hit(&mut world.events, e, &other_player, weapon_type, damage);
                 if e.damage(damage) {
-                    let player_id = {
-                        let mut other_player = other_player.borrow_player_mut();
-                        other_player.score += kill_score(e.borrow_player().score);
-                        let player_id = other_player.player_id;
-                        drop(other_player);
-                        player_id
-                    };
+                    let score = kill_score(e.borrow_player().score);
+                    let player_id =
+                        Self::credit_kill(world, index, &other_player, weapon_type, score);
 
                     Self::boat_died(world, index, false);
                     world.remove(index, DeathReason::Weapon(player_id, weapon_type));
@@ -142,17 +171,26 @@ impl Mutation {
                 damage,
                 impulse,
                 other_player,
+                other_entity_type,
                 ram,
             } => {
                 let entity = &mut entities[index];
+                entity.extension_mut().damage_contributors.add(
+                    &other_player,
+                    other_entity_type,
+                    damage,
+                );
+                Self::hit(
+                    &mut world.events,
+                    entity,
+                    &other_player,
+                    other_entity_type,
+                    damage,
+                );
                 if entity.damage(damage) {
-                    let player_id = {
-                        let mut other_player = other_player.borrow_player_mut();
-                        other_player.score += ram_score(entity.borrow_player().score);
-                        let player_id = other_player.player_id;
-                        drop(other_player);
-                        player_id
-                    };
+                    let score = ram_score(entity.borrow_player().score);
+                    let player_id =
+                        Self::credit_kill(world, index, &other_player, other_entity_type, score);
 
                     Self::boat_died(world, index, false);
                     world.remove(
@@ -181,10 +219,27 @@ impl Mutation {
                 entity.transform.velocity =
                     (entity.transform.velocity + impulse).clamp_magnitude(Velocity::from_mps(20.0));
             }
+            Self::Afflict {
+                affliction,
+                other_player,
+                weapon_type,
+            } => {
+                entities[index]
+                    .extension_mut()
+                    .affliction_mut(affliction)
+                    .add(&other_player, weapon_type);
+            }
+            Self::Extinguish => {
+                let extension = entities[index].extension_mut();
+                for affliction in Affliction::ALL {
+                    extension.affliction_mut(affliction).remove();
+                }
+            }
+            Self::Bracket => entities[index].extension_mut().bracket(),
             Self::ClearSpawnProtection => entities[index].extension_mut().clear_spawn_protection(),
             Self::UpgradeHq => {
                 let entity = &mut entities[index];
-                entity.change_entity_type(EntityType::Hq, &mut world.arena);
+                entity.change_entity_type(EntityType::Hq, 0, &mut world.arena);
                 entity.ticks = Ticks::ZERO;
             }
             Self::Repair(amount) => {
@@ -202,8 +257,25 @@ impl Mutation {
             Self::Score(score) => {
                 entities[index].borrow_player_mut().score += score;
             }
+            Self::Salvage(amount) => {
+                let entity = &mut entities[index];
+                entity.ticks = entity.ticks.saturating_add(amount);
+                if entity.ticks > entity.data().lifespan {
+                    world.remove(index, DeathReason::Unknown);
+                    return true;
+                }
+            }
             Self::CollectedBy(player, score) => {
-                player.borrow_player_mut().score += score;
+                let mut player = player.borrow_player_mut();
+                player.score += score;
+                world.events.push((
+                    player.player_id,
+                    Event::Collected {
+                        entity_type: entities[index].entity_type,
+                        score,
+                    },
+                ));
+                drop(player);
                 world.remove(index, DeathReason::Unknown);
                 return true;
             }
@@ -249,7 +321,7 @@ impl Mutation {
                                     150.0 / armament_data.speed.to_mps().clamp(15.0, 50.0),
                                 ));
                             armament_entity.transform =
-                                entity.transform + data.armament_transform(&[], i);
+                                entity.transform + data.armament_transform(0, &[], i);
                             armament_entity.altitude = entity.altitude;
                             armament_entity.guidance = Guidance {
                                 direction_target: entity.transform.direction, // TODO: Randomize
@@ -278,6 +350,96 @@ impl Mutation {
         false
     }
 
+    /// hit records events for a boat being hit by another player's weapon (or boat, if rammed).
+    fn hit(
+        events: &mut Vec<(PlayerId, Event)>,
+        boat: &Entity,
+        attacker: &Arc<PlayerTuple<Server>>,
+        weapon_type: EntityType,
+        damage: Ticks,
+    ) {
+        if damage == Ticks::ZERO {
+            return;
+        }
+
+        let attacker = attacker.borrow_player().player_id;
+        let victim = boat.borrow_player().player_id;
+
+        events.push((
+            attacker,
+            Event::Hit {
+                target: boat.id,
+                victim,
+                entity_type: boat.entity_type,
+                weapon_type,
+                damage,
+            },
+        ));
+        events.push((
+            victim,
+            Event::HitBy {
+                attacker,
+                weapon_type,
+                damage,
+            },
+        ));
+    }
+
+    /// credit_kill awards the score for sinking a boat, plus any bounty on it, to the killer,
+    /// sharing some of it with any other players who recently damaged the boat, and records the
+    /// corresponding events. Returns the killer's player id.
+    pub fn credit_kill(
+        world: &mut World,
+        index: EntityIndex,
+        killer: &Arc<PlayerTuple<Server>>,
+        weapon_type: EntityType,
+        score: u32,
+    ) -> PlayerId {
+        let entity = &world.entities[index];
+        let (victim, bounty) = {
+            let victim = entity.borrow_player();
+            (victim.player_id, victim.data.bounty)
+        };
+        let (killer_score, assists) = entity
+            .extension()
+            .damage_contributors
+            .split(killer, score + bounty);
+
+        for (player, weapon_type, score) in assists {
+            let mut player = player.borrow_player_mut();
+            player.score += score;
+            world.events.push((
+                player.player_id,
+                Event::Assist {
+                    victim,
+                    entity_type: entity.entity_type,
+                    weapon_type,
+                    score,
+                },
+            ));
+        }
+
+        let mut killer = killer.borrow_player_mut();
+        killer.score += killer_score;
+        killer.data.kill_streak += 1;
+        if bounty > 0 && !killer.player_id.is_npc() {
+            world.announcements.push((
+                format!("{{0}} claimed the bounty of {} on {{1}}!", bounty),
+                vec![killer.player_id, victim],
+            ));
+        }
+        world.events.push((
+            killer.player_id,
+            Event::Sunk {
+                victim,
+                entity_type: entity.entity_type,
+                weapon_type,
+                score: killer_score,
+            },
+        ));
+        killer.player_id
+    }
+
     /// boat_died applies the effect of a boat dying, such as a reduction in the corresponding player's
     /// score and the spawning of loot.
     ///
@@ -288,6 +450,9 @@ impl Mutation {
         let mut player = entity.borrow_player_mut();
         let score = player.score;
         player.score = respawn_score(player.score);
+        player.data.kill_streak = 0;
+        player.data.bounty = 0;
+        player.data.consumables.clear();
         drop(player);
 
         let data = entity.data();
@@ -296,7 +461,8 @@ impl Mutation {
         // Loot is based on the length of the boat.
 
         let center = entity.transform.position;
-        let normal = entity.transform.direction.to_vec();
+        let direction = entity.transform.direction;
+        let normal = direction.to_vec();
         let tangent = Vec2::new(-normal.y, normal.x);
         let altitude = entity.altitude;
 
@@ -317,6 +483,26 @@ impl Mutation {
 
             world.spawn_here_or_nearby(loot_entity, data.radius * 0.15, None);
         }
+
+        // Leave a wreck, of the closest size to the boat, on the seabed.
+        let wreck_type = EntityType::iter()
+            .filter(|t| t.data().sub_kind == EntitySubKind::Wreck)
+            .min_by(|a, b| {
+                let a_diff = (a.data().length - data.length).abs();
+                let b_diff = (b.data().length - data.length).abs();
+                a_diff.partial_cmp(&b_diff).unwrap()
+            });
+        if let Some(wreck_type) = wreck_type {
+            let mut wreck = Entity::new(wreck_type, None);
+            wreck.transform.position = center;
+            wreck.transform.direction = direction;
+            wreck.altitude = world
+                .terrain
+                .sample(center)
+                .unwrap_or(Altitude::MIN)
+                .min(-Altitude::UNIT);
+            world.add(wreck);
+        }
     }
 
     /// Call when a weapon, decoy, or aircraft dies and the player may still be alive, so it may be
@@ -341,11 +527,11 @@ impl Mutation {
         );
 
         let boat = &mut world.entities[boat_index];
-        let data = boat.data();
+        let armaments = boat.armaments();
         let extension = boat.extension_mut();
         let consumption = extension.reloads_mut();
 
-        for (i, armament) in data.armaments.iter().enumerate() {
+        for (i, armament) in armaments.iter().enumerate() {
             if armament.entity_type != entity_type || consumption[i] != Ticks::MAX {
                 continue;
             }
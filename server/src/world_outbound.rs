@@ -8,6 +8,8 @@ use crate::player::Status;
 use crate::server::Server;
 use crate::world::World;
 use common::entity::{EntityData, EntityKind, EntitySubKind};
+use common::jammer::Jammer;
+use common::storm::Storm;
 use common::ticks::Ticks;
 use common::util::*;
 use game_server::context::PlayerTuple;
@@ -52,14 +54,51 @@ impl World {
             // Radar and visual don't work well under water.
             let visual_radar_efficacy = map_ranges(norm_altitude, -0.35..0.0, 0.0..1.0, true);
 
-            let visual = sensors.visual.range * visual_radar_efficacy;
-            let radar = sensors.radar.range * visual_radar_efficacy;
+            // Masts carry visual and radar sensors.
+            let mast_efficacy = entity.extension().subsystems.sensor_factor();
+
+            // Such as a sensor boost.
+            let boost = entity.extension().consumable_effects.sensor_factor();
+
+            // Enemy jammers pointed at the entity degrade its radar.
+            let jamming_factor = if self
+                .entities
+                .iter_radius(entity.transform.position, Jammer::MAX_RANGE)
+                .any(|(_, e)| !e.is_friendly(entity) && e.is_jamming(entity.transform.position))
+            {
+                Jammer::RADAR_FACTOR
+            } else {
+                1.0
+            };
+
+            // Storms obscure the view out of them.
+            let weather_factor = if self
+                .storm
+                .as_ref()
+                .map_or(false, |storm| storm.contains(entity.transform.position))
+            {
+                Storm::SENSOR_FACTOR
+            } else {
+                1.0
+            };
+
+            let visual = sensors.visual.range
+                * visual_radar_efficacy
+                * mast_efficacy
+                * boost
+                * weather_factor;
+            let radar = sensors.radar.range
+                * visual_radar_efficacy
+                * mast_efficacy
+                * boost
+                * jamming_factor
+                * weather_factor;
 
             // Sonar works at full effective range as long as it is not airborne.
             let sonar = if entity.altitude.is_airborne() {
                 0.0
             } else {
-                sensors.sonar.range
+                sensors.sonar.range * boost
             };
 
             if player.data.status.is_alive() {
@@ -186,6 +225,11 @@ impl World {
                         uncertainty = uncertainty.min(passive_radar_ratio);
                     }
 
+                    if player_entity.is_some() && !friendly && entity.is_jamming(camera_pos) {
+                        // Jamming gives away the jammer's bearing, but not what it is.
+                        uncertainty = uncertainty.min(Jammer::REVEAL_UNCERTAINTY);
+                    }
+
                     if sonar_range_inv.is_finite() && !altitude.is_airborne() {
                         let mut sonar_ratio = default_ratio * sonar_range_inv;
                         if camera.active {
@@ -212,6 +256,9 @@ impl World {
                                 // Active sonar gives away entity's position.
                                 noise += 20.0;
                             }
+                        } else if data.sub_kind == EntitySubKind::Wreck {
+                            // Wrecks creak and groan as they settle.
+                            noise += 8.0;
                         }
 
                         sonar_ratio /= noise;
@@ -236,6 +283,14 @@ impl World {
                             visual_ratio /=
                                 map_ranges(altitude.to_norm(), -0.5..1.0, min..0.8, true);
                         }
+                        if data.kind == EntityKind::Boat {
+                            // Such as a smoke screen.
+                            visual_ratio /= entity
+                                .extension()
+                                .consumable_effects
+                                .visibility_factor()
+                                .powi(2);
+                        }
                         visible = visual_ratio < 1.0;
                         uncertainty = uncertainty.min(visual_ratio);
                     }
@@ -252,6 +307,11 @@ impl World {
                         uncertainty = 0.4;
                     }
 
+                    if data.kind == EntityKind::Boat && entity.extension().is_bracketed() {
+                        // Splashes around a boat give away its position.
+                        uncertainty = uncertainty.min(0.4);
+                    }
+
                     if uncertainty >= 1.0 {
                         // This player has no knowledge of this entity,
                         // so it is not a contact.
@@ -265,7 +325,13 @@ impl World {
                     || uncertainty < 0.5
                     || distance_squared < inner_circle_squared;
 
-                Some(ContactRef::new(entity, visible, known, has_type))
+                Some(ContactRef::new(
+                    entity,
+                    visible,
+                    known,
+                    has_type,
+                    same_player,
+                ))
             });
 
         // How much more terrain can be sent.
@@ -3,16 +3,18 @@
 
 use crate::entities::EntityIndex;
 use crate::entity::Entity;
+use crate::noise::current_generator;
 use crate::player::{Flags, Status};
 use crate::world::World;
 use crate::world_mutation::Mutation;
+use common::affliction::Affliction;
 use common::altitude::Altitude;
 use common::angle::Angle;
 use common::death_reason::DeathReason;
 use common::entity::*;
 use common::terrain::TerrainMutation;
 use common::ticks::Ticks;
-use common::util::map_ranges;
+use common::util::{kill_score, map_ranges};
 use common::velocity::Velocity;
 use common::world::{area_border_normal, clamp_y_to_area_border, outside_area, ARCTIC};
 use glam::Vec2;
@@ -40,6 +42,8 @@ impl World {
         let border_radius = self.radius; // Avoids double borrow.
         let border_radius_squared = self.radius.powi(2);
         let terrain = &self.terrain;
+        let current_seconds = self.current_seconds;
+        let harbors = &self.harbors;
 
         // Collected updates (order doesn't matter).
         let limited_reloads = Mutex::new(Vec::new()); // Of form (player_entity_index, limited_entity_type).
@@ -47,6 +51,7 @@ impl World {
         let terrain_mutations = Mutex::new(Vec::new());
         let barrel_spawns = Mutex::new(Vec::new());
         let reset_flags = Mutex::new(Vec::new());
+        let afflicted_kills = Mutex::new(Vec::new()); // Of form (victim index, killer, weapon type, score).
 
         // Call when any entity that is potentially a weapon is removed, to make sure it is reloaded
         // if it is a limited armament. No need to call if the player is definitely not alive.
@@ -106,6 +111,16 @@ impl World {
                     }
                 }
 
+                let in_harbor = harbors
+                    .iter()
+                    .any(|harbor| harbor.contains(entity.transform.position));
+
+                if in_harbor && matches!(data.kind, EntityKind::Weapon | EntityKind::Aircraft) {
+                    // Harbors are no-weapons zones.
+                    potential_limited_reload(entity);
+                    return Some((index, Fate::Remove(DeathReason::Unknown)));
+                }
+
                 let mut max_speed = data.speed.to_mps();
                 let mut repair_eligible = true;
 
@@ -157,8 +172,11 @@ impl World {
                         entity.apply_altitude_target(terrain, None, 4.0, delta);
                     }
                     EntityKind::Collectible | EntityKind::Weapon | EntityKind::Decoy => {
-                        let altitude_change =
-                            entity.apply_altitude_target(terrain, None, 3.0, delta);
+                        let altitude_change = if data.is_ballistic() {
+                            entity.apply_ballistic_arc(delta)
+                        } else {
+                            entity.apply_altitude_target(terrain, None, 3.0, delta)
+                        };
                         if entity.altitude.is_submerged() {
                             match data.sub_kind {
                                 // Wait until risen to surface.
@@ -190,6 +208,8 @@ impl World {
                         }
                     }
                     EntityKind::Boat => {
+                        max_speed *= entity.extension().subsystems.speed_factor();
+
                         entity.apply_altitude_target(
                             terrain,
                             Some(entity.extension().altitude_target),
@@ -228,9 +248,22 @@ impl World {
                     .apply_guidance(data, entity.guidance, max_speed, delta_seconds);
                 entity.transform.do_kinematics(delta_seconds);
 
+                // Ocean currents carry floating entities.
+                let drift = data.current_drift();
+                if drift > 0.0 && !entity.altitude.is_airborne() {
+                    entity.transform.position +=
+                        current_generator(entity.transform.position, current_seconds)
+                            * (drift * delta_seconds);
+                }
+
                 let arctic = entity.transform.position.y >= common::world::ARCTIC;
 
-                let collision = entity.collides_with_terrain(terrain, delta_seconds);
+                // Wrecks rest on the seabed, however close to shore.
+                let collision = if data.sub_kind == EntitySubKind::Wreck {
+                    None
+                } else {
+                    entity.collides_with_terrain(terrain, delta_seconds)
+                };
 
                 // An entity colliding with terrain/water when it shouldn't has consequences.
                 if collision.is_some() != data.is_land_based() {
@@ -352,19 +385,66 @@ impl World {
 
                 if data.kind == EntityKind::Boat {
                     entity.update_turret_aim(delta_seconds);
-                    entity.reload(delta);
-                    entity
-                        .extension_mut()
-                        .update_active_cooldown_and_spawn_protection(delta);
-
-                    if repair_eligible {
-                        let repair_amount = if data.length > 200.0 {
-                            3.0
-                        } else if data.length > 100.0 {
-                            2.0
-                        } else {
-                            1.0
-                        };
+                    entity.extension_mut().update_timers(delta);
+
+                    let mut repair_amount = if data.length > 200.0 {
+                        3.0
+                    } else if data.length > 100.0 {
+                        2.0
+                    } else {
+                        1.0
+                    };
+
+                    if in_harbor {
+                        // Deployed limited armaments are only restocked when they return to
+                        // the harbor and are removed, like any other weapon in a harbor.
+                        entity.reload(delta * Self::HARBOR_RELOAD_FACTOR);
+                        repair_amount *= Self::HARBOR_REPAIR_FACTOR;
+                    } else {
+                        entity.reload(delta);
+                    }
+
+                    if entity.extension().afflictions().any() {
+                        // Repair time goes towards putting out fires and stopping floods, instead
+                        // of repairing the boat.
+                        for affliction in Affliction::ALL {
+                            let state = entity.extension_mut().affliction_mut(affliction);
+                            let stacks = state.stacks();
+                            if stacks == 0 {
+                                continue;
+                            }
+                            let source = state.source();
+                            if repair_eligible {
+                                state.subside(delta * repair_amount);
+                            }
+
+                            let kill_time = affliction.kill_time() * (1.0 / stacks as f32);
+                            if let Some((source, weapon_type)) = &source {
+                                let damage = entity.damage_in(delta, kill_time);
+                                entity.extension_mut().damage_contributors.add(
+                                    source,
+                                    *weapon_type,
+                                    damage,
+                                );
+                            }
+
+                            if entity.kill_in(delta, kill_time) {
+                                let reason = if let Some((killer, weapon_type)) = source {
+                                    let player_id = killer.borrow_player().player_id;
+                                    afflicted_kills.lock().unwrap().push((
+                                        index,
+                                        killer,
+                                        weapon_type,
+                                        kill_score(entity.borrow_player().score),
+                                    ));
+                                    DeathReason::Weapon(player_id, weapon_type)
+                                } else {
+                                    DeathReason::Unknown
+                                };
+                                return Some((index, Fate::Remove(reason)));
+                            }
+                        }
+                    } else if repair_eligible {
                         entity.repair(delta * repair_amount);
                     }
 
@@ -386,6 +466,10 @@ impl World {
             .collect();
 
         // Must do before removing any entities (and invalidating indices).
+        for (index, killer, weapon_type, score) in afflicted_kills.into_inner().unwrap() {
+            Mutation::credit_kill(self, index, &killer, weapon_type, score);
+        }
+
         for (player_entity_index, limited_entity_type) in limited_reloads.into_inner().unwrap() {
             Mutation::reload_limited_armament(
                 self,
@@ -475,7 +559,7 @@ impl World {
                 Fate::DowngradeHq => {
                     let entity = &mut self.entities[index];
                     entity.ticks = Ticks::ZERO;
-                    entity.change_entity_type(EntityType::OilPlatform, &mut self.arena);
+                    entity.change_entity_type(EntityType::OilPlatform, 0, &mut self.arena);
                 }
             }
         }
@@ -7,9 +7,12 @@ use crate::player::Status;
 use crate::world::World;
 use crate::world_mutation::Mutation;
 use arrayvec::ArrayVec;
+use common::affliction::Affliction;
 use common::angle::Angle;
 use common::death_reason::DeathReason;
 use common::entity::*;
+use common::protocol::AntiAircraftFire;
+use common::subsystems::Subsystems;
 use common::ticks;
 use common::ticks::Ticks;
 use common::util::hash_u32_to_f32;
@@ -18,6 +21,7 @@ use rand::{thread_rng, Rng};
 use rayon::prelude::*;
 use server_util::benchmark::Timer;
 use server_util::benchmark_scope;
+use std::collections::BTreeSet;
 use std::sync::Arc;
 use std::sync::Mutex;
 
@@ -35,6 +39,9 @@ impl World {
             EntityKind::Aircraft | EntityKind::Weapon => {
                 // Enough for guidance, deploying sub-armaments, etc.
                 radius = radius.max(data.sensors.max_range());
+                if let Some(seeker) = &data.seeker {
+                    radius = radius.max(seeker.range);
+                }
             }
             _ => {}
         }
@@ -67,6 +74,10 @@ impl World {
 
         // TODO: look into lock free data structures.
         let mutations = Mutex::new(Vec::new());
+        let anti_aircraft_fire = Mutex::new(Vec::new());
+        // Aircraft shot down this tick, so that each is only refunded and removed once, even if
+        // several boats shoot it down.
+        let downed_aircraft = Mutex::new(BTreeSet::new());
 
         // Call when any entity that is potentially a weapon is removed, to make sure it is reloaded
         // if it is a limited armament. No need to call if the player is definitely not alive.
@@ -100,6 +111,95 @@ impl World {
                     return; // continue
                 }
 
+                if data.is_ballistic() && entity.ticks.saturating_add(delta) > data.lifespan {
+                    // Ballistic shell is landing, so it damages boats within its splash radius and
+                    // reveals boats that it narrowly missed.
+                    const SPLASH_DAMAGE: f32 = 0.5;
+
+                    let splash_position = entity.transform.position;
+                    let mut mutations = mutations.lock().unwrap();
+                    for (boat_index, boat) in self.entities.iter_radius(
+                        splash_position,
+                        data.splash * EntityData::BRACKET_FACTOR,
+                    ) {
+                        let boat_data = boat.data();
+                        if boat_data.kind != EntityKind::Boat
+                            || boat.altitude.is_submerged()
+                            || entity.is_friendly(boat)
+                        {
+                            continue;
+                        }
+
+                        let distance = boat.transform.position.distance(splash_position);
+                        if distance < data.splash {
+                            let damage_resistance = boat_data.resistance_to_subkind(data.sub_kind)
+                                * boat.extension().spawn_protection();
+                            let falloff = 1.0 - distance / data.splash;
+                            let damage = ticks::from_damage(
+                                data.damage * SPLASH_DAMAGE * falloff * damage_resistance,
+                            );
+
+                            mutations.push((
+                                boat_index,
+                                Mutation::HitBy {
+                                    other_player: Arc::clone(entity.player.as_ref().unwrap()),
+                                    weapon_type: entity.entity_type,
+                                    damage,
+                                    subsystem: None,
+                                },
+                            ));
+                        } else {
+                            mutations.push((boat_index, Mutation::Bracket));
+                        }
+                    }
+                    mutations.push((index, Mutation::Remove(DeathReason::Unknown)));
+                    return; // continue
+                }
+
+                if let Some(anti_aircraft) = &data.anti_aircraft {
+                    if !entity.altitude.is_submerged() {
+                        // Automatic anti-aircraft engages all hostile aircraft within its range
+                        // and arc, dividing its fire if there are more of them than channels.
+                        let position = entity.transform.position;
+                        let targets: Vec<_> = self
+                            .entities
+                            .iter_radius(position, anti_aircraft.range)
+                            .filter(|(_, aircraft)| {
+                                aircraft.data().kind == EntityKind::Aircraft
+                                    && !entity.is_friendly(aircraft)
+                                    && aircraft.transform.position.distance(position)
+                                        <= anti_aircraft.range
+                                    && anti_aircraft.covers(&entity.transform, aircraft.transform.position)
+                            })
+                            .collect();
+
+                        for &(aircraft_index, aircraft) in targets.iter() {
+                            anti_aircraft_fire.lock().unwrap().push((
+                                position,
+                                AntiAircraftFire {
+                                    boat: entity.id,
+                                    aircraft: aircraft.id,
+                                },
+                            ));
+
+                            let chance = anti_aircraft.kill_chance(
+                                aircraft.transform.position.distance(position),
+                                aircraft.transform.velocity.abs().to_mps(),
+                                targets.len(),
+                            ) * delta_seconds;
+                            if thread_rng().gen_bool((chance as f64).clamp(0.0, 1.0))
+                                && downed_aircraft.lock().unwrap().insert(aircraft_index)
+                            {
+                                potential_limited_reload(aircraft, false);
+                                mutations
+                                    .lock()
+                                    .unwrap()
+                                    .push((aircraft_index, Mutation::Remove(DeathReason::Unknown)));
+                            }
+                        }
+                    }
+                }
+
                 let radius = Self::minimum_scan_radius(entity, delta_seconds);
 
                 for (other_index, other_entity) in self.entities.iter_radius(
@@ -163,6 +263,43 @@ impl World {
                         }
                     }
 
+                    if obstacles.len() == 1 && obstacles[0].data().sub_kind == EntitySubKind::Wreck {
+                        // Wrecks rest on the seabed, out of everything's way, but can be salvaged
+                        // for score.
+                        if boats.len() == 1 && boats[0].can_salvage(obstacles[0]) {
+                            let wreck_data = obstacles[0].data();
+                            let salvaged = delta * Entity::SALVAGE_FACTOR;
+                            let score = wreck_data.value as f32 * salvaged.to_secs() / wreck_data.lifespan.to_secs();
+
+                            let score = random_round(score);
+
+                            mutate(obstacles[0], Mutation::Salvage(salvaged));
+                            if score > 0 {
+                                mutate(boats[0], Mutation::Score(score));
+                            }
+                        }
+                        continue;
+                    }
+
+                    if boats.len() == 2 && friendly {
+                        // Tankers resupply teammates stationed alongside them, for score.
+                        for (tanker, teammate) in [(entity, other_entity), (other_entity, entity)] {
+                            if tanker.can_resupply(teammate) {
+                                let amount = delta * Entity::RESUPPLY_FACTOR;
+                                mutate(teammate, Mutation::Repair(amount));
+                                mutate(teammate, Mutation::Reload(amount));
+
+                                // Only damage actually repaired counts.
+                                let repaired = teammate.ticks.min(amount);
+                                let score =
+                                    random_round(repaired.to_secs() * Entity::RESUPPLY_SCORE);
+                                if score > 0 {
+                                    mutate(tanker, Mutation::Score(score));
+                                }
+                            }
+                        }
+                    }
+
                     if !entity.collides_with(other_entity, delta_seconds) || !altitude_overlap {
                         if collectibles.len() == 1 && altitude_overlap {
                             // Collectibles gravitate towards players (except if the player created them).
@@ -192,20 +329,20 @@ impl World {
                                 let target = if weapon == &entity { other_entity } else { entity };
                                 let target_data = target.data();
 
-                                if weapon_data.sensors.any() && !matches!(weapon_data.sub_kind, EntitySubKind::Rocket | EntitySubKind::RocketTorpedo) {
-                                    // Home towards target/decoy
-                                    // Sensor activates after 1 second.
-                                    if weapons[0].ticks > Ticks::from_secs(1.0) {
+                                if let Some(seeker) = &weapon_data.seeker {
+                                    // Home towards target/decoy, once the seeker starts searching.
+                                    if weapon.ticks > seeker.delay {
                                         // Different targets are relevant to each weapon.
                                         let relevant = match weapon_data.sub_kind {
                                             EntitySubKind::Sam => {
                                                 target_data.kind == EntityKind::Aircraft || matches!(target_data.sub_kind, EntitySubKind::Missile | EntitySubKind::Rocket | EntitySubKind::RocketTorpedo)
                                             },
                                             EntitySubKind::Torpedo => {
-                                                target_data.kind == EntityKind::Boat || target_data.kind == EntityKind::Decoy
+                                                target_data.kind == EntityKind::Boat || (target_data.kind == EntityKind::Decoy && target_data.sub_kind == EntitySubKind::Sonar)
                                             },
                                             EntitySubKind::Missile => {
-                                                target_data.kind == EntityKind::Boat && weapon.altitude_overlapping(target)
+                                                // Chaff spoofs radar-guided missiles.
+                                                (target_data.kind == EntityKind::Boat || (target_data.kind == EntityKind::Decoy && target_data.sub_kind == EntitySubKind::Radar)) && weapon.altitude_overlapping(target)
                                             }
                                             _ => {
                                                 target_data.kind == EntityKind::Boat
@@ -219,36 +356,30 @@ impl World {
                                             let seeker_position = weapon.transform.position + weapon.transform.direction.to_vec() * weapon.transform.velocity.to_mps().max(2.0);
                                             let target_position = target.closest_point_on_keel_to(seeker_position, 0.5);
                                             let diff = target_position - weapon.transform.position;
-                                            let distance_squared = diff.length_squared();
+                                            let distance = diff.length();
                                             let angle = Angle::from(diff);
 
                                             // Should not exceed range.
                                             let remaining_range = weapon.transform.velocity.to_mps() * weapon.data().lifespan.saturating_sub(weapon.ticks).to_secs() + 30.0;
                                             // Should not go off target.
                                             let angle_target_diff = (angle - weapon.guidance.direction_target).abs();
-                                            // Cannot sense beyond this angle.
-                                            let angle_diff = (angle - weapon.transform.direction).abs();
-
-                                            if distance_squared <= remaining_range.powi(2) && angle_target_diff <= Angle::from_degrees(60.0) && angle_diff <= Angle::from_degrees(80.0) {
-                                                let mut size = target_data.radius;
-                                                if target_data.kind == EntityKind::Decoy {
-                                                    // Decoys appear very large to weapons.
-                                                    size += 200.0;
-                                                } else if target_data.kind == EntityKind::Boat && target_data.sensors.any() && target.extension().is_active() {
-                                                    // So do boats with active sensors.
-                                                    size += 75.0;
-                                                }
 
-                                                // Altitude diff.
-                                                let altitude_diff = weapon.altitude.difference(target.altitude).to_norm();
-
-                                                let randomness = hash_u32_to_f32(target.id.get() ^ weapon.id.get());
-                                                let strength = size / EntityData::MAX_RADIUS
-                                                    - distance_squared / radius.powi(2)
-                                                    - angle_diff.to_radians() / Angle::MAX.to_radians()
-                                                    - altitude_diff
-                                                    + (1.0 / 3.0) * randomness;
-                                                mutate(weapon, Mutation::Guidance {direction_target: angle, altitude_target: target.altitude, signal_strength: strength});
+                                            if distance <= remaining_range && angle_target_diff <= Angle::from_degrees(60.0) {
+                                                // Targets compete for the lock (the strongest is followed), so decoys
+                                                // only work if they stand out more than the boat they protect.
+                                                let emitting = target_data.kind == EntityKind::Boat && target_data.sensors.any() && target.extension().is_active();
+                                                let signature = seeker.signature(target_data, target.transform.velocity.abs().to_mps(), emitting);
+
+                                                if let Some(lock_strength) = seeker.lock_strength(signature, distance, angle - weapon.transform.direction) {
+                                                    // Altitude diff.
+                                                    let altitude_diff = weapon.altitude.difference(target.altitude).to_norm();
+
+                                                    let randomness = hash_u32_to_f32(target.id.get() ^ weapon.id.get());
+                                                    let strength = lock_strength
+                                                        - altitude_diff
+                                                        + (1.0 / 3.0) * randomness;
+                                                    mutate(weapon, Mutation::Guidance {direction_target: angle, altitude_target: target.altitude, signal_strength: strength});
+                                                }
                                             }
                                         }
                                     }
@@ -305,20 +436,6 @@ impl World {
                                     }
                                 }
 
-                                // Automatic anti-aircraft has a chance of killing aircraft.
-                                if target_data.anti_aircraft > 0.0 && weapon_data.kind == EntityKind::Aircraft && target_data.kind == EntityKind::Boat {
-                                    let d2 = weapon.transform.position.distance_squared(target.transform.position);
-                                    let r2 = target_data.anti_aircraft_range().powi(2);
-
-                                    // In range of aa.
-                                    if d2 <= r2 {
-                                        let chance = (1.0 - d2/r2) * target_data.anti_aircraft * delta.to_secs();
-                                        if thread_rng().gen_bool((chance as f64).clamp(0.0, 1.0)) {
-                                            potential_limited_reload(weapon, false);
-                                            debug_remove!(weapon, "shot down");
-                                        }
-                                    }
-                                }
                             }
                         } else if boats.len() == 1 && weapons.len() == 1 && boats[0].has_same_player(weapons[0]) &&
                             weapons[0].data().kind == EntityKind::Aircraft &&
@@ -329,34 +446,18 @@ impl World {
                             debug_remove!(weapons[0], "landed");
                         }
 
-                        /*
-                        if otherData.AntiAircraft != 0 && entityData.Kind == world.EntityKindAircraft {
-                            d2 := entity.Position.DistanceSquared(other.Position)
-                            r2 := square(otherData.Radius * 1.5)
-
-                            // In range of aa
-                            if d2 < r2 {
-                                chance := (1.0 - d2/r2) * otherData.AntiAircraft
-                                if chance*timeDeltaSeconds > rand.Float32() {
-                                    removeEntity(entity, world.DeathReason{})
-                                }
-                            }
-                        }
-                         */
-
                         continue;
                     }
 
                     #[allow(clippy::if_same_then_else)]
                     if boats.len() == 1 && collectibles.len() == 1 {
-                        let is_tanker = boats[0].data().sub_kind == EntitySubKind::Tanker;
-                        let score = match collectibles[0].entity_type {
-                            EntityType::Barrel => 1 + is_tanker as u32,
-                            EntityType::Coin => 10,
-                            EntityType::Crate => 2,
-                            EntityType::Scrap => 2,
-                            _ => 0,
-                        };
+                        let collectible_data = collectibles[0].data();
+                        let score = collectible_data.value
+                            + collectible_data
+                                .value_bonus
+                                .get(&boats[0].data().sub_kind)
+                                .copied()
+                                .unwrap_or(0);
 
                         mutate(
                             collectibles[0],
@@ -368,8 +469,11 @@ impl World {
 
                         // Collecting your own coins does not have auxiliary benefits.
                         if !friendly {
-                            mutate(boats[0], Mutation::Repair(Ticks::from_secs(1.5)));
-                            mutate(boats[0], Mutation::Reload(collectibles[0].data().reload));
+                            mutate(boats[0], Mutation::Repair(collectible_data.repair));
+                            mutate(boats[0], Mutation::Reload(collectible_data.reload));
+                            if collectible_data.extinguish {
+                                mutate(boats[0], Mutation::Extinguish);
+                            }
                         }
                     } else if boats.len() == 2 {
                         /*
@@ -450,7 +554,7 @@ impl World {
                             // Velocity change to cause repulsion.
                             let impulse = Velocity::from_mps(6.0 * pos_diff_closest_point_on_other_keel.dot(boat.transform.direction.to_vec()) * relative_mass);
 
-                            mutate(boat, Mutation::CollidedWithBoat{other_player: Arc::clone(other_boat.player.as_ref().unwrap()), damage, ram: other_data.sub_kind == EntitySubKind::Ram, impulse});
+                            mutate(boat, Mutation::CollidedWithBoat{other_player: Arc::clone(other_boat.player.as_ref().unwrap()), other_entity_type: other_boat.entity_type, damage, ram: other_data.sub_kind == EntitySubKind::Ram, impulse});
                         }
                     } else if boats.len() == 1 && weapons.len() == 1 && !friendly {
                         let boat_data = boats[0].data();
@@ -468,14 +572,32 @@ impl World {
                             weapon_data.damage * collision_multiplier(d2, r2, boat_data.sub_kind == EntitySubKind::Submarine) * damage_resistance,
                         );
 
+                        // Where the weapon hit, relative to the boat.
+                        let impact = weapons[0].impact_with(boats[0], delta_seconds).unwrap_or(weapons[0].transform.position);
+                        let hit_position = boats[0].transform.to_local(impact);
+                        let subsystem = Subsystems::locate(boat_data, boats[0].extension().loadout, boats[0].extension().turrets.as_ref(), hit_position);
+
                         mutate(
                             boats[0],
-                            Mutation::HitBy(
-                                Arc::clone(weapons[0].player.as_ref().unwrap()),
-                                weapons[0].entity_type,
+                            Mutation::HitBy {
+                                other_player: Arc::clone(weapons[0].player.as_ref().unwrap()),
+                                weapon_type: weapons[0].entity_type,
                                 damage,
-                            ),
+                                subsystem,
+                            },
                         );
+                        if let Some((affliction, chance)) = Affliction::caused_by(weapon_data.sub_kind) {
+                            if thread_rng().gen_bool(chance) {
+                                mutate(
+                                    boats[0],
+                                    Mutation::Afflict {
+                                        affliction,
+                                        other_player: Arc::clone(weapons[0].player.as_ref().unwrap()),
+                                        weapon_type: weapons[0].entity_type,
+                                    },
+                                );
+                            }
+                        }
                         potential_limited_reload(weapons[0], false);
                         debug_remove!(weapons[0], "hit");
                     } else if boats.len() == 1 && obstacles.len() == 1 {
@@ -530,6 +652,8 @@ impl World {
                 }
             });
 
+        self.anti_aircraft_fire = anti_aircraft_fire.into_inner().unwrap();
+
         let mut mutations = mutations.into_inner().unwrap();
 
         // Sort by reverse EntityIndex while prioritizing Mutation ordering.
@@ -562,6 +686,12 @@ impl World {
     }
 }
 
+/// Rounds a non-negative amount up or down randomly, in proportion to its fractional part, so
+/// that small amounts (e.g. of score per tick) add up over time.
+fn random_round(amount: f32) -> u32 {
+    amount as u32 + thread_rng().gen_bool(amount.fract().clamp(0.0, 1.0) as f64) as u32
+}
+
 /// Computes multiplier for damage such that hits closer to center of boat do more damage.
 /// Graph comparing old system (red) to new system (sub yellow, boat red): https://www.desmos.com/calculator/crwtc3u4f3
 fn collision_multiplier(d2: f32, r2: f32, is_sub: bool) -> f32 {
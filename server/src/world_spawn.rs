@@ -141,7 +141,9 @@ impl World {
                     .entities
                     .iter_radius(entity.transform.position, max_collision_radius)
                 {
-                    if other_entity.data().kind == EntityKind::Obstacle
+                    let other_data = other_entity.data();
+                    if other_data.kind == EntityKind::Obstacle
+                        && other_data.sub_kind != EntitySubKind::Wreck
                         && entity.collides_with(other_entity, 0.0)
                     {
                         // Cannot spawn
@@ -174,8 +176,10 @@ impl World {
         {
             let other_data = other_entity.data();
 
-            if other_data.kind == EntityKind::Collectible {
-                // Collectibles don't block spawning.
+            if other_data.kind == EntityKind::Collectible
+                || other_data.sub_kind == EntitySubKind::Wreck
+            {
+                // Collectibles and wrecks don't block spawning.
                 continue;
             }
 
@@ -0,0 +1,136 @@
+// SPDX-FileCopyrightText: 2021 Softbear, Inc.
+// SPDX-License-Identifier: AGPL-3.0-or-later
+
+use common::entity::{EntityKind, EntityType};
+use glam::Vec2;
+use image::{io::Reader, RgbaImage};
+use rayon::prelude::*;
+use std::collections::BTreeMap;
+use std::fs;
+
+/// Pixels at least this opaque are considered part of the boat.
+const ALPHA_THRESHOLD: u8 = 128;
+
+/// Hulls are simplified to at most this many vertices, to keep collisions cheap.
+const MAX_VERTICES: usize = 12;
+
+/// write_hulls traces a simplified convex hull of each boat's sprite, and writes them to a json
+/// file (to be merged into entity data by the preprocessor). Vertices are counter-clockwise,
+/// with the bow at positive x, and normalized such that the sprite spans -0.5 to 0.5 on each axis.
+pub(crate) fn write_hulls(output_data: &str) {
+    let hulls: BTreeMap<String, Vec<[f32; 2]>> = EntityType::iter()
+        .filter(|entity_type| entity_type.data().kind == EntityKind::Boat)
+        .collect::<Vec<_>>()
+        .par_iter()
+        .map(|entity_type| {
+            let image_name = entity_type.to_string();
+            let image_path = format!("../assets/models/rendered/{}.png", &image_name);
+
+            println!("Tracing hull of {}...", image_path);
+
+            let image = Reader::open(&image_path)
+                .unwrap()
+                .decode()
+                .unwrap()
+                .into_rgba8();
+
+            let hull = simplify(convex_hull(outline(&image)), MAX_VERTICES)
+                .into_iter()
+                .map(|v| [round(v.x), round(v.y)])
+                .collect();
+
+            (image_name, hull)
+        })
+        .collect();
+
+    let data_path = format!("{}.json", output_data);
+    println!("Writing {}...", data_path);
+    fs::write(&data_path, serde_json::to_string_pretty(&hulls).unwrap()).unwrap();
+}
+
+/// outline returns the normalized corners of the leftmost and rightmost opaque pixels of each row,
+/// which are the only pixels that can contribute to the convex hull.
+fn outline(image: &RgbaImage) -> Vec<Vec2> {
+    let width = image.width() as f32;
+    let height = image.height() as f32;
+    let normalize = |x: u32, y: u32| Vec2::new(x as f32 / width - 0.5, 0.5 - y as f32 / height);
+
+    let mut points = Vec::new();
+    for y in 0..image.height() {
+        let mut opaque =
+            (0..image.width()).filter(|&x| image.get_pixel(x, y).0[3] >= ALPHA_THRESHOLD);
+        if let Some(min_x) = opaque.next() {
+            let max_x = opaque.last().unwrap_or(min_x);
+            for (x, y) in [
+                (min_x, y),
+                (min_x, y + 1),
+                (max_x + 1, y),
+                (max_x + 1, y + 1),
+            ] {
+                points.push(normalize(x, y));
+            }
+        }
+    }
+    points
+}
+
+/// convex_hull returns the counter-clockwise convex hull of a set of points (monotone chain).
+fn convex_hull(mut points: Vec<Vec2>) -> Vec<Vec2> {
+    points.sort_by(|a, b| {
+        a.x.partial_cmp(&b.x)
+            .unwrap()
+            .then(a.y.partial_cmp(&b.y).unwrap())
+    });
+    points.dedup();
+
+    if points.len() < 3 {
+        return points;
+    }
+
+    let mut hull: Vec<Vec2> = Vec::with_capacity(points.len() * 2);
+
+    // Lower hull, followed by upper hull.
+    for iteration in 0..2 {
+        let start = hull.len();
+        for &p in points.iter() {
+            while hull.len() >= start + 2
+                && (hull[hull.len() - 1] - hull[hull.len() - 2]).perp_dot(p - hull[hull.len() - 2])
+                    <= 0.0
+            {
+                hull.pop();
+            }
+            hull.push(p);
+        }
+        // Last point is the first point of the other half.
+        hull.pop();
+
+        if iteration == 0 {
+            points.reverse();
+        }
+    }
+
+    hull
+}
+
+/// simplify removes the vertices of a convex polygon that contribute the least area, until at most
+/// max_vertices remain.
+fn simplify(mut polygon: Vec<Vec2>, max_vertices: usize) -> Vec<Vec2> {
+    while polygon.len() > max_vertices.max(3) {
+        let n = polygon.len();
+        let (least, _) = (0..n)
+            .map(|i| {
+                let previous = polygon[(i + n - 1) % n];
+                let next = polygon[(i + 1) % n];
+                (i, (polygon[i] - previous).perp_dot(next - previous).abs())
+            })
+            .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+            .unwrap();
+        polygon.remove(least);
+    }
+    polygon
+}
+
+/// round keeps the json concise.
+fn round(v: f32) -> f32 {
+    (v * 1000.0).round() / 1000.0
+}
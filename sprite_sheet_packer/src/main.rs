@@ -4,9 +4,11 @@
 #![feature(exit_status_error)]
 
 mod audio;
+mod hull;
 mod texture;
 
 use crate::audio::pack_audio_sprite_sheet;
+use crate::hull::write_hulls;
 use crate::texture::{pack_sprite_sheet, webpify, EntityPackParams};
 use common::entity::{EntityData, EntityKind, EntityType};
 use common::util::map_ranges;
@@ -26,6 +28,9 @@ fn main() {
         "../assets/sounds/README",
     );
 
+    // Collision shapes, which the preprocessor merges into entity data.
+    write_hulls("../data/hulls");
+
     //return;
 
     // NOTE: Pre-multiplication is not compatible with WebP, so avoid doing it here.